@@ -0,0 +1,392 @@
+//! A public representation of a parsed Doxygen comment.
+//!
+//! A [`Comment`] is made of [`Block`]s, and each block contains [`Inline`] nodes. Block commands
+//! (like `@param`, `@note` or `@return`) start a new block which lasts until the next block
+//! command or the next blank line, while inline commands (like `@b` or `@c`) are kept inside the
+//! text they appear in.
+//!
+//! Text is kept as it appears in the comment, including the indentation of every line. Runs of
+//! spaces inside a line are collapsed into one, unless the line is indented like a code block
+//! (by four or more columns), where they may be part of its layout. List items
+//! (`-`, `-#`, `@li` and `@arg`) are marked inside the text of their paragraph, along with how
+//! deeply they are nested.
+//!
+//! # Examples
+//!
+//! ```
+//! use doxygen_rs::ast::{Block, Inline};
+//!
+//! let comment = doxygen_rs::parse("@param[in] value The @b value to use").unwrap();
+//! let Block::Command { command, content, .. } = &comment.blocks[0] else {
+//!     panic!("expected a block command");
+//! };
+//!
+//! assert_eq!(command.name, "param");
+//! assert_eq!(command.meta, vec!["in"]);
+//! assert_eq!(command.arguments, vec!["value"]);
+//! assert!(matches!(&content[1], Inline::Command(command) if command.name == "b"));
+//! ```
+
+use crate::commands::is_block_command;
+use crate::lexer::{Span, Spanned};
+use crate::parser::GrammarItem;
+use crate::ParseError;
+
+/// A whole Doxygen comment.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Comment {
+    pub blocks: Vec<Block>,
+}
+
+/// A paragraph-level element of a [`Comment`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Block {
+    /// Text that isn't introduced by any block command.
+    Paragraph { content: Vec<Inline>, span: Span },
+    /// A block command and the content that belongs to it.
+    Command {
+        command: Command,
+        content: Vec<Inline>,
+        span: Span,
+    },
+    /// The start of a member group (`@{`).
+    GroupStart { span: Span },
+    /// The end of a member group (`@}`).
+    GroupEnd { span: Span },
+    /// A block whose content is kept as written, like `@code{.c} ... @endcode` or
+    /// `@verbatim ... @endverbatim`.
+    Verbatim {
+        /// The command that opened the block, like `code` or `verbatim`.
+        command: String,
+        /// The language given with `@code{.lang}`, if any.
+        language: Option<String>,
+        content: String,
+        span: Span,
+    },
+}
+
+impl Block {
+    /// Returns the region of the comment covered by this block.
+    pub fn span(&self) -> Span {
+        match self {
+            Block::Paragraph { span, .. }
+            | Block::Command { span, .. }
+            | Block::GroupStart { span }
+            | Block::GroupEnd { span }
+            | Block::Verbatim { span, .. } => *span,
+        }
+    }
+
+    /// Returns the inline content of this block, which is empty for group markers and verbatim
+    /// blocks.
+    pub fn content(&self) -> &[Inline] {
+        match self {
+            Block::Paragraph { content, .. } | Block::Command { content, .. } => content,
+            Block::GroupStart { .. } | Block::GroupEnd { .. } | Block::Verbatim { .. } => &[],
+        }
+    }
+}
+
+/// An element inside a [`Block`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Inline {
+    Text {
+        text: String,
+        span: Span,
+    },
+    /// An inline command, like `@b` or `@c`.
+    Command(Command),
+    /// The start of a list item, whose text is the one that follows it.
+    ListItem {
+        /// The number of lists the item is nested in, `0` for the outermost list.
+        depth: usize,
+        /// Whether the item is numbered (`-#`).
+        ordered: bool,
+        span: Span,
+    },
+    /// A `.` on its own line, which ends the lists nested deeper than `depth`.
+    ListEnd {
+        depth: usize,
+        span: Span,
+    },
+}
+
+/// A Doxygen command, like `@param[in] name`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Command {
+    /// The name of the command, without the leading `@` or `\`.
+    pub name: String,
+    /// The options of the command, like the direction (`in`, `out`) of a `@param`.
+    pub meta: Vec<String>,
+    /// The arguments of the command, like the name of a `@param`.
+    pub arguments: Vec<String>,
+    pub span: Span,
+}
+
+/// Parses a comment whose decorations were already removed.
+pub(crate) fn parse(source: &str) -> Result<Comment, ParseError> {
+    Ok(build(source, crate::parser::parse(source.into())?))
+}
+
+/// Builds a [`Comment`] out of the items parsed from `source`.
+fn build(source: &str, items: Vec<Spanned<GrammarItem>>) -> Comment {
+    let mut blocks = vec![];
+
+    for Spanned { item, span } in items {
+        match item {
+            GrammarItem::Notation { meta, params, tag } => {
+                let command = Command {
+                    name: tag,
+                    meta,
+                    arguments: params,
+                    span,
+                };
+
+                if is_block_command(&command.name) {
+                    blocks.push(Block::Command {
+                        command,
+                        content: vec![],
+                        span,
+                    });
+                } else {
+                    push_inline(&mut blocks, Inline::Command(command), span);
+                }
+            }
+            GrammarItem::Text(text) => {
+                for (index, (text, span)) in split_paragraphs(source, &text, span)
+                    .into_iter()
+                    .enumerate()
+                {
+                    if index > 0 {
+                        blocks.push(Block::Paragraph {
+                            content: vec![],
+                            span,
+                        });
+                    }
+
+                    if !text.is_empty() {
+                        push_inline(&mut blocks, Inline::Text { text, span }, span);
+                    }
+                }
+            }
+            GrammarItem::ListItem { depth, ordered } => push_inline(
+                &mut blocks,
+                Inline::ListItem {
+                    depth,
+                    ordered,
+                    span,
+                },
+                span,
+            ),
+            GrammarItem::ListEnd { depth } => {
+                push_inline(&mut blocks, Inline::ListEnd { depth, span }, span)
+            }
+            GrammarItem::GroupStart => blocks.push(Block::GroupStart { span }),
+            GrammarItem::GroupEnd => blocks.push(Block::GroupEnd { span }),
+            GrammarItem::Verbatim {
+                command,
+                language,
+                content,
+            } => blocks.push(Block::Verbatim {
+                command,
+                language,
+                content,
+                span,
+            }),
+        }
+    }
+
+    // Blank lines at the end of a text start a paragraph that may never get any content
+    blocks.retain(|block| !matches!(block, Block::Paragraph { content, .. } if content.is_empty()));
+
+    Comment { blocks }
+}
+
+fn push_inline(blocks: &mut Vec<Block>, inline: Inline, inline_span: Span) {
+    match blocks.last_mut() {
+        Some(Block::Paragraph { content, span } | Block::Command { content, span, .. }) => {
+            *span = span.to(inline_span);
+            content.push(inline);
+        }
+        _ => blocks.push(Block::Paragraph {
+            content: vec![inline],
+            span: inline_span,
+        }),
+    }
+}
+
+/// Splits `text` at every blank line, returning each piece along with its span in `source`.
+///
+/// The first newline before a blank line is kept in the previous piece, while the blank lines
+/// themselves are dropped.
+fn split_paragraphs(source: &str, text: &str, span: Span) -> Vec<(String, Span)> {
+    let slice = source.get(span.start..span.end).unwrap_or(text);
+    let text_breaks = blank_lines(text);
+    let source_breaks = blank_lines(slice);
+
+    if text_breaks.is_empty() || text_breaks.len() != source_breaks.len() {
+        return vec![(text.into(), span)];
+    }
+
+    let mut result = vec![];
+    let mut text_start = 0;
+    let mut source_start = 0;
+    for ((text_break, text_end), (source_break, source_end)) in
+        text_breaks.into_iter().zip(source_breaks)
+    {
+        result.push((
+            text[text_start..text_break].into(),
+            sub_span(source, span, source_start, source_break),
+        ));
+        text_start = text_end;
+        source_start = source_end;
+    }
+    result.push((
+        text[text_start..].into(),
+        sub_span(source, span, source_start, slice.len()),
+    ));
+
+    result
+}
+
+/// Finds the blank lines in `text`, returning for each run of them the offset right after the
+/// newline that precedes them and the offset where the next paragraph starts.
+fn blank_lines(text: &str) -> Vec<(usize, usize)> {
+    let mut result: Vec<(usize, usize)> = vec![];
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let end = offset + line.len();
+
+        if offset > 0 && line.ends_with('\n') && line.trim().is_empty() {
+            match result.last_mut() {
+                Some((_, next)) if *next == offset => *next = end,
+                _ => result.push((offset, end)),
+            }
+        }
+
+        offset = end;
+    }
+
+    result
+}
+
+fn sub_span(source: &str, span: Span, start: usize, end: usize) -> Span {
+    let Some(before) = source.get(span.start..span.start + start) else {
+        return span;
+    };
+
+    let (line, column) = match before.rfind('\n') {
+        Some(index) => (
+            span.line + before.matches('\n').count(),
+            before[index + 1..].chars().count() + 1,
+        ),
+        None => (span.line, span.column + before.chars().count()),
+    };
+
+    Span {
+        start: span.start + start,
+        end: span.start + end,
+        line,
+        column,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(text: &str, start: usize, end: usize, line: usize, column: usize) -> Inline {
+        Inline::Text {
+            text: text.into(),
+            span: Span {
+                start,
+                end,
+                line,
+                column,
+            },
+        }
+    }
+
+    #[test]
+    fn block_and_inline_commands() {
+        let result = parse("@brief A @b bold brief").unwrap();
+        assert_eq!(result.blocks.len(), 1);
+
+        let Block::Command {
+            command, content, ..
+        } = &result.blocks[0]
+        else {
+            panic!("expected a block command, found {:?}", result.blocks[0]);
+        };
+        assert_eq!(command.name, "brief");
+        assert_eq!(content.len(), 3);
+        assert_eq!(content[0], text("A ", 7, 9, 1, 8));
+        assert!(matches!(
+            &content[1],
+            Inline::Command(Command { name, arguments, .. }) if name == "b" && arguments == &["bold"]
+        ));
+        assert_eq!(content[2], text(" brief", 16, 22, 1, 17));
+    }
+
+    #[test]
+    fn paragraphs() {
+        let result =
+            parse("First paragraph\n\n  \nSecond paragraph\n@note A note\nwith two lines\n\nThird")
+                .unwrap();
+        assert_eq!(
+            result.blocks.iter().map(Block::content).collect::<Vec<_>>(),
+            vec![
+                &[text("First paragraph\n", 0, 16, 1, 1)][..],
+                &[text("Second paragraph\n", 20, 37, 4, 1)][..],
+                &[text("A note\nwith two lines\n", 43, 65, 5, 7)][..],
+                &[text("Third", 66, 71, 8, 1)][..],
+            ]
+        );
+        assert!(matches!(result.blocks[0], Block::Paragraph { .. }));
+        assert!(matches!(result.blocks[2], Block::Command { .. }));
+        assert!(matches!(result.blocks[3], Block::Paragraph { .. }));
+    }
+
+    #[test]
+    fn inline_command_after_blank_line() {
+        let result = parse("@note A note\n\n@b Bold").unwrap();
+        assert_eq!(result.blocks.len(), 2);
+        assert!(matches!(
+            &result.blocks[1],
+            Block::Paragraph { content, .. } if matches!(&content[0], Inline::Command(_))
+        ));
+    }
+
+    #[test]
+    fn verbatim() {
+        let result =
+            parse("@param value The value, like:\n@code{.c}\nint value = 1; // @b\n@endcode\nDone")
+                .unwrap();
+        assert_eq!(result.blocks.len(), 3);
+        assert!(matches!(
+            &result.blocks[1],
+            Block::Verbatim { command, language: Some(language), content, .. }
+                if command == "code" && language == "c" && content == "int value = 1; // @b"
+        ));
+        assert!(matches!(&result.blocks[2], Block::Paragraph { .. }));
+    }
+
+    #[test]
+    fn groups() {
+        let result = parse("@name Memory Management\n@{\nText\n@}").unwrap();
+        assert!(matches!(
+            &result.blocks[..],
+            [
+                Block::Command { .. },
+                Block::GroupStart { .. },
+                Block::Paragraph { .. },
+                Block::GroupEnd { .. }
+            ]
+        ));
+    }
+}
@@ -0,0 +1,254 @@
+//! A ready-made [`ParseCallbacks`] implementation for bindgen.
+//!
+//! Requires the `bindgen` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use doxygen_rs::bindgen::DoxygenCallbacks;
+//! use doxygen_rs::TransformOptions;
+//!
+//! #[derive(Debug)]
+//! struct MyCallbacks;
+//!
+//! impl bindgen::callbacks::ParseCallbacks for MyCallbacks {
+//!     fn item_name(&self, item_info: bindgen::callbacks::ItemInfo) -> Option<String> {
+//!         Some(item_info.name.trim_start_matches("lib_").into())
+//!     }
+//! }
+//!
+//! let bindings = bindgen::Builder::default()
+//!     .header("wrapper.h")
+//!     .parse_callbacks(Box::new(
+//!         DoxygenCallbacks::new()
+//!             .with_options(TransformOptions::new().heading_level(2))
+//!             .with_callbacks(Box::new(MyCallbacks)),
+//!     ))
+//!     .generate()
+//!     .unwrap();
+//! ```
+
+use ::bindgen::callbacks::{
+    AttributeInfo, DeriveInfo, DeriveTrait, DiscoveredItem, DiscoveredItemId,
+    EnumVariantCustomBehavior, EnumVariantValue, FieldInfo, ImplementsTrait, IntKind, ItemInfo,
+    MacroParsingBehavior, ParseCallbacks, Token,
+};
+use ::bindgen::FieldVisibilityKind;
+use std::sync::{Mutex, PoisonError};
+
+use crate::{TransformOptions, Transformer};
+
+/// Transforms every comment found by bindgen with a [`Transformer`], so member groups opened in
+/// one comment are kept until they are closed. Their members are only marked when
+/// [`TransformOptions::group_style`] is set.
+///
+/// Bindgen only asks the last registered callbacks to process comments, so other callbacks can be
+/// given with [`DoxygenCallbacks::with_callbacks`]: every other callback is forwarded to them,
+/// and the comments they process are transformed afterwards.
+///
+/// Comments that can't be transformed are left untouched instead of panicking.
+#[derive(Debug, Default)]
+pub struct DoxygenCallbacks {
+    transformer: Mutex<Transformer>,
+    inner: Option<Box<dyn ParseCallbacks>>,
+}
+
+impl DoxygenCallbacks {
+    /// Creates the callbacks with the default [`TransformOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the options used to transform the comments.
+    pub fn with_options(mut self, options: TransformOptions) -> Self {
+        self.transformer = Mutex::new(Transformer::with_options(options));
+        self
+    }
+
+    /// Sets the callbacks every other call is forwarded to.
+    pub fn with_callbacks(mut self, callbacks: Box<dyn ParseCallbacks>) -> Self {
+        self.inner = Some(callbacks);
+        self
+    }
+}
+
+impl ParseCallbacks for DoxygenCallbacks {
+    fn will_parse_macro(&self, name: &str) -> MacroParsingBehavior {
+        self.inner
+            .as_ref()
+            .map(|v| v.will_parse_macro(name))
+            .unwrap_or_default()
+    }
+
+    fn generated_name_override(&self, item_info: ItemInfo<'_>) -> Option<String> {
+        self.inner.as_ref()?.generated_name_override(item_info)
+    }
+
+    fn generated_link_name_override(&self, item_info: ItemInfo<'_>) -> Option<String> {
+        self.inner.as_ref()?.generated_link_name_override(item_info)
+    }
+
+    fn modify_macro(&self, name: &str, tokens: &mut Vec<Token>) {
+        if let Some(inner) = &self.inner {
+            inner.modify_macro(name, tokens)
+        }
+    }
+
+    fn int_macro(&self, name: &str, value: i64) -> Option<IntKind> {
+        self.inner.as_ref()?.int_macro(name, value)
+    }
+
+    fn str_macro(&self, name: &str, value: &[u8]) {
+        if let Some(inner) = &self.inner {
+            inner.str_macro(name, value)
+        }
+    }
+
+    fn func_macro(&self, name: &str, value: &[&[u8]]) {
+        if let Some(inner) = &self.inner {
+            inner.func_macro(name, value)
+        }
+    }
+
+    fn enum_variant_behavior(
+        &self,
+        enum_name: Option<&str>,
+        original_variant_name: &str,
+        variant_value: EnumVariantValue,
+    ) -> Option<EnumVariantCustomBehavior> {
+        self.inner
+            .as_ref()?
+            .enum_variant_behavior(enum_name, original_variant_name, variant_value)
+    }
+
+    fn enum_variant_name(
+        &self,
+        enum_name: Option<&str>,
+        original_variant_name: &str,
+        variant_value: EnumVariantValue,
+    ) -> Option<String> {
+        self.inner
+            .as_ref()?
+            .enum_variant_name(enum_name, original_variant_name, variant_value)
+    }
+
+    fn item_name(&self, item_info: ItemInfo) -> Option<String> {
+        self.inner.as_ref()?.item_name(item_info)
+    }
+
+    fn header_file(&self, filename: &str) {
+        if let Some(inner) = &self.inner {
+            inner.header_file(filename)
+        }
+    }
+
+    fn include_file(&self, filename: &str) {
+        if let Some(inner) = &self.inner {
+            inner.include_file(filename)
+        }
+    }
+
+    fn read_env_var(&self, key: &str) {
+        if let Some(inner) = &self.inner {
+            inner.read_env_var(key)
+        }
+    }
+
+    fn blocklisted_type_implements_trait(
+        &self,
+        name: &str,
+        derive_trait: DeriveTrait,
+    ) -> Option<ImplementsTrait> {
+        self.inner
+            .as_ref()?
+            .blocklisted_type_implements_trait(name, derive_trait)
+    }
+
+    fn add_derives(&self, info: &DeriveInfo<'_>) -> Vec<String> {
+        self.inner
+            .as_ref()
+            .map(|v| v.add_derives(info))
+            .unwrap_or_default()
+    }
+
+    fn add_attributes(&self, info: &AttributeInfo<'_>) -> Vec<String> {
+        self.inner
+            .as_ref()
+            .map(|v| v.add_attributes(info))
+            .unwrap_or_default()
+    }
+
+    fn process_comment(&self, comment: &str) -> Option<String> {
+        let processed = self.inner.as_ref().and_then(|v| v.process_comment(comment));
+        let comment = processed.as_deref().unwrap_or(comment);
+
+        self.transformer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .transform(comment)
+            .ok()
+            .or(processed)
+    }
+
+    fn field_visibility(&self, info: FieldInfo<'_>) -> Option<FieldVisibilityKind> {
+        self.inner.as_ref()?.field_visibility(info)
+    }
+
+    fn new_item_found(&self, id: DiscoveredItemId, item: DiscoveredItem) {
+        if let Some(inner) = &self.inner {
+            inner.new_item_found(id, item)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GroupStyle;
+
+    #[derive(Debug)]
+    struct Uppercase;
+
+    impl ParseCallbacks for Uppercase {
+        fn process_comment(&self, comment: &str) -> Option<String> {
+            Some(comment.replace("value", "VALUE"))
+        }
+
+        fn int_macro(&self, _name: &str, _value: i64) -> Option<IntKind> {
+            Some(IntKind::U8)
+        }
+    }
+
+    #[test]
+    fn process_comment() {
+        let callbacks = DoxygenCallbacks::new();
+        assert_eq!(
+            callbacks.process_comment("@param value The value"),
+            Some("# Arguments\n\n* `value` - The value".into())
+        );
+        assert_eq!(callbacks.process_comment("@b"), None);
+    }
+
+    #[test]
+    fn keeps_groups() {
+        let callbacks = DoxygenCallbacks::new()
+            .with_options(TransformOptions::new().group_style(GroupStyle::Annotate));
+        callbacks.process_comment("@name Group\n@{\nFirst");
+        assert_eq!(
+            callbacks.process_comment("Second"),
+            Some("Second\n\n> **Group:** Group".into())
+        );
+    }
+
+    #[test]
+    fn composes() {
+        let callbacks = DoxygenCallbacks::new()
+            .with_options(TransformOptions::new().heading_level(2))
+            .with_callbacks(Box::new(Uppercase));
+        assert_eq!(
+            callbacks.process_comment("@param value The value"),
+            Some("## Arguments\n\n* `VALUE` - The VALUE".into())
+        );
+        assert!(matches!(callbacks.int_macro("MACRO", 1), Some(IntKind::U8)));
+    }
+}
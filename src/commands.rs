@@ -0,0 +1,397 @@
+use phf::phf_map;
+
+/// The kind of an argument of a Doxygen command, following the notation of the Doxygen manual.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum ArgumentKind {
+    /// `<word>`, a single word.
+    Word,
+    /// `"text"`, a quoted text which can have spaces.
+    Quoted,
+    /// `(line)`, the rest of the line.
+    Line,
+    /// `{paragraph}`, everything until a blank line or the next section command.
+    Paragraph,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) struct Argument {
+    pub(crate) kind: ArgumentKind,
+    /// Whether the argument was written between `[]` in the manual.
+    pub(crate) optional: bool,
+}
+
+const fn required(kind: ArgumentKind) -> Argument {
+    Argument {
+        kind,
+        optional: false,
+    }
+}
+
+const fn optional(kind: ArgumentKind) -> Argument {
+    Argument {
+        kind,
+        optional: true,
+    }
+}
+
+const WORD: Argument = required(ArgumentKind::Word);
+const OPTIONAL_WORD: Argument = optional(ArgumentKind::Word);
+const QUOTED: Argument = required(ArgumentKind::Quoted);
+const OPTIONAL_QUOTED: Argument = optional(ArgumentKind::Quoted);
+const LINE: Argument = required(ArgumentKind::Line);
+const OPTIONAL_LINE: Argument = optional(ArgumentKind::Line);
+const PARAGRAPH: Argument = required(ArgumentKind::Paragraph);
+
+const NONE: &[Argument] = &[];
+const HEADER: &[Argument] = &[WORD, OPTIONAL_WORD, OPTIONAL_WORD];
+const DESCRIBED: &[Argument] = &[WORD, PARAGRAPH];
+const FILE: &[Argument] = &[WORD];
+const SNIPPET: &[Argument] = &[WORD, LINE];
+const SECTION: &[Argument] = &[WORD, LINE];
+// The `<sizeindication>=<size>` that can follow the caption is left as text
+const DIAGRAM_FILE: &[Argument] = &[WORD, OPTIONAL_QUOTED];
+
+type Commands = phf::Map<&'static str, &'static [Argument]>;
+
+/// The arguments of every command documented in the Doxygen manual
+/// (<https://www.doxygen.nl/manual/commands.html>), grouped like in the manual, without the
+/// `{option}` or `[option]` that can follow the name of some commands.
+///
+/// Only the `<word>` and `"text"` arguments before the first line or paragraph are parsed as the
+/// arguments of a command, the rest is left as the text that follows it.
+pub(crate) static COMMANDS: [&Commands; 5] = [
+    &STRUCTURAL_INDICATORS,
+    &SECTION_INDICATORS,
+    &LINKS,
+    &EXAMPLES,
+    &VISUAL_ENHANCEMENTS,
+];
+
+static STRUCTURAL_INDICATORS: Commands = phf_map! {
+    "addtogroup" => &[WORD, OPTIONAL_LINE],
+    "callgraph" => NONE,
+    "hidecallgraph" => NONE,
+    "callergraph" => NONE,
+    "hidecallergraph" => NONE,
+    "showrefby" => NONE,
+    "hiderefby" => NONE,
+    "showrefs" => NONE,
+    "hiderefs" => NONE,
+    "showinlinesource" => NONE,
+    "hideinlinesource" => NONE,
+    "includegraph" => NONE,
+    "hideincludegraph" => NONE,
+    "includedbygraph" => NONE,
+    "hideincludedbygraph" => NONE,
+    "directorygraph" => NONE,
+    "hidedirectorygraph" => NONE,
+    "collaborationgraph" => NONE,
+    "hidecollaborationgraph" => NONE,
+    "inheritancegraph" => NONE,
+    "hideinheritancegraph" => NONE,
+    "groupgraph" => NONE,
+    "hidegroupgraph" => NONE,
+    "showenumvalues" => NONE,
+    "hideenumvalues" => NONE,
+    "qualifier" => &[WORD],
+    "category" => HEADER,
+    "class" => HEADER,
+    "concept" => &[WORD],
+    "def" => &[WORD],
+    "defgroup" => &[WORD, LINE],
+    "dir" => &[OPTIONAL_WORD],
+    "enum" => &[WORD],
+    "example" => FILE,
+    "extends" => &[WORD],
+    "file" => &[OPTIONAL_WORD],
+    "fileinfo" => NONE,
+    "lineinfo" => NONE,
+    "fn" => &[LINE],
+    "headerfile" => &[WORD, OPTIONAL_WORD],
+    "hideinitializer" => NONE,
+    "idlexcept" => &[WORD],
+    "implements" => &[WORD],
+    "ingroup" => &[LINE],
+    "interface" => HEADER,
+    "internal" => NONE,
+    "endinternal" => NONE,
+    "mainpage" => &[OPTIONAL_LINE],
+    "memberof" => &[WORD],
+    "module" => &[WORD],
+    "name" => &[OPTIONAL_LINE],
+    "namespace" => &[WORD],
+    "nosubgrouping" => NONE,
+    "overload" => &[OPTIONAL_LINE],
+    "package" => &[WORD],
+    "page" => &[WORD, LINE],
+    "private" => NONE,
+    "privatesection" => NONE,
+    "property" => &[LINE],
+    "protected" => NONE,
+    "protectedsection" => NONE,
+    "protocol" => HEADER,
+    "public" => NONE,
+    "publicsection" => NONE,
+    "pure" => NONE,
+    "relates" => &[WORD],
+    "related" => &[WORD],
+    "relatesalso" => &[WORD],
+    "relatedalso" => &[WORD],
+    "showinitializer" => NONE,
+    "static" => NONE,
+    "struct" => HEADER,
+    "typedef" => &[LINE],
+    "union" => HEADER,
+    "var" => &[LINE],
+    "vhdlflow" => &[OPTIONAL_LINE],
+    "weakgroup" => &[WORD, OPTIONAL_LINE],
+};
+
+static SECTION_INDICATORS: Commands = phf_map! {
+    "attention" => &[PARAGRAPH],
+    "author" => &[PARAGRAPH],
+    "authors" => &[PARAGRAPH],
+    "brief" => &[PARAGRAPH],
+    "bug" => &[PARAGRAPH],
+    "cond" => &[OPTIONAL_LINE],
+    "copyright" => &[PARAGRAPH],
+    "date" => &[PARAGRAPH],
+    "showdate" => &[QUOTED, OPTIONAL_LINE],
+    "deprecated" => &[PARAGRAPH],
+    "details" => &[PARAGRAPH],
+    "noop" => &[LINE],
+    "raisewarning" => &[LINE],
+    "else" => NONE,
+    "elseif" => &[LINE],
+    "endcond" => NONE,
+    "endif" => NONE,
+    "exception" => DESCRIBED,
+    "if" => &[LINE],
+    "ifnot" => &[LINE],
+    "important" => &[PARAGRAPH],
+    "invariant" => &[PARAGRAPH],
+    "note" => &[PARAGRAPH],
+    "par" => &[OPTIONAL_LINE, PARAGRAPH],
+    "param" => DESCRIBED,
+    "parblock" => NONE,
+    "endparblock" => NONE,
+    "tparam" => DESCRIBED,
+    "post" => &[PARAGRAPH],
+    "pre" => &[PARAGRAPH],
+    "remark" => &[PARAGRAPH],
+    "remarks" => &[PARAGRAPH],
+    "result" => &[PARAGRAPH],
+    "return" => &[PARAGRAPH],
+    "returns" => &[PARAGRAPH],
+    "retval" => DESCRIBED,
+    "sa" => &[PARAGRAPH],
+    "see" => &[PARAGRAPH],
+    "short" => &[PARAGRAPH],
+    "since" => &[PARAGRAPH],
+    "test" => &[PARAGRAPH],
+    "throw" => DESCRIBED,
+    "throws" => DESCRIBED,
+    "todo" => &[PARAGRAPH],
+    "version" => &[PARAGRAPH],
+    "warning" => &[PARAGRAPH],
+    "xrefitem" => &[WORD, QUOTED, QUOTED, PARAGRAPH],
+};
+
+static LINKS: Commands = phf_map! {
+    "addindex" => &[LINE],
+    "anchor" => &[WORD],
+    "cite" => &[WORD],
+    "endlink" => NONE,
+    "link" => &[WORD],
+    "ref" => &[WORD, OPTIONAL_QUOTED],
+    "refitem" => &[WORD],
+    "secreflist" => NONE,
+    "endsecreflist" => NONE,
+    "subpage" => &[WORD, OPTIONAL_QUOTED],
+    "tableofcontents" => NONE,
+    "section" => SECTION,
+    "subsection" => SECTION,
+    "subsubsection" => SECTION,
+    "paragraph" => SECTION,
+    "subparagraph" => SECTION,
+    "subsubparagraph" => SECTION,
+};
+
+static EXAMPLES: Commands = phf_map! {
+    "dontinclude" => FILE,
+    "include" => FILE,
+    "includelineno" => FILE,
+    "includedoc" => FILE,
+    "line" => &[LINE],
+    "skip" => &[LINE],
+    "skipline" => &[LINE],
+    "snippet" => SNIPPET,
+    "snippetlineno" => SNIPPET,
+    "snippetdoc" => SNIPPET,
+    "until" => &[LINE],
+    "verbinclude" => FILE,
+    "htmlinclude" => FILE,
+    "latexinclude" => FILE,
+    "rtfinclude" => FILE,
+    "maninclude" => FILE,
+    "docbookinclude" => FILE,
+    "xmlinclude" => FILE,
+};
+
+static VISUAL_ENHANCEMENTS: Commands = phf_map! {
+    "a" => &[WORD],
+    "arg" => &[PARAGRAPH],
+    "b" => &[WORD],
+    "c" => &[WORD],
+    "code" => NONE,
+    "copydoc" => &[WORD],
+    "copybrief" => &[WORD],
+    "copydetails" => &[WORD],
+    "docbookonly" => NONE,
+    "dot" => &[OPTIONAL_QUOTED],
+    "emoji" => &[WORD],
+    "msc" => &[OPTIONAL_QUOTED],
+    "startuml" => &[OPTIONAL_QUOTED],
+    "dotfile" => DIAGRAM_FILE,
+    "mscfile" => DIAGRAM_FILE,
+    "diafile" => DIAGRAM_FILE,
+    "plantumlfile" => DIAGRAM_FILE,
+    "doxyconfig" => &[WORD],
+    "e" => &[WORD],
+    "em" => &[WORD],
+    "endcode" => NONE,
+    "enddocbookonly" => NONE,
+    "enddot" => NONE,
+    "endmsc" => NONE,
+    "enduml" => NONE,
+    "endhtmlonly" => NONE,
+    "endlatexonly" => NONE,
+    "endmanonly" => NONE,
+    "endrtfonly" => NONE,
+    "endverbatim" => NONE,
+    "endxmlonly" => NONE,
+    "f$" => NONE,
+    "f(" => NONE,
+    "f)" => NONE,
+    "f[" => NONE,
+    "f]" => NONE,
+    // The `{environment}{` that follows is left as text
+    "f{" => NONE,
+    "f}" => NONE,
+    "htmlonly" => NONE,
+    "iliteral" => NONE,
+    "endiliteral" => NONE,
+    "image" => &[WORD, WORD, OPTIONAL_QUOTED],
+    "latexonly" => NONE,
+    "manonly" => NONE,
+    "li" => &[PARAGRAPH],
+    "n" => NONE,
+    "p" => &[WORD],
+    "rtfonly" => NONE,
+    "verbatim" => NONE,
+    "xmlonly" => NONE,
+    "$" => NONE,
+    "@" => NONE,
+    "\\" => NONE,
+    "&" => NONE,
+    // The `[LanguageId]` that can follow it is left as text
+    "~" => NONE,
+    "<" => NONE,
+    "=" => NONE,
+    ">" => NONE,
+    "#" => NONE,
+    "%" => NONE,
+    "\"" => NONE,
+    "." => NONE,
+    "::" => NONE,
+    "|" => NONE,
+    "--" => NONE,
+    "---" => NONE,
+};
+
+/// Returns the arguments of `command`, or none if it isn't a Doxygen command.
+pub(crate) fn arguments(command: &str) -> &'static [Argument] {
+    COMMANDS
+        .iter()
+        .find_map(|v| v.get(command))
+        .copied()
+        .unwrap_or(NONE)
+}
+
+/// Returns whether `command` starts a block of its own: the structural indicators, and the
+/// section indicators whose last argument is a paragraph, like `@param` or `@note`.
+pub(crate) fn is_block_command(command: &str) -> bool {
+    STRUCTURAL_INDICATORS.contains_key(command)
+        || SECTION_INDICATORS
+            .get(command)
+            .and_then(|v| v.last())
+            .is_some_and(|v| v.kind == ArgumentKind::Paragraph)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        assert_eq!(arguments("tparam"), &[WORD, PARAGRAPH]);
+        assert_eq!(arguments("ref"), &[WORD, OPTIONAL_QUOTED]);
+        assert_eq!(arguments("xrefitem")[1].kind, ArgumentKind::Quoted);
+        assert!(arguments("thisdoesntexist").is_empty());
+    }
+
+    /// The commands listed in <https://www.doxygen.nl/manual/commands.html>.
+    const MANUAL: &str = "\
+        a addindex addtogroup anchor arg attention author authors b brief bug c callergraph \
+        callgraph category cite class code collaborationgraph concept cond copybrief copydetails \
+        copydoc copyright date def defgroup deprecated details diafile dir directorygraph \
+        docbookinclude docbookonly dontinclude dot dotfile doxyconfig e else elseif em emoji \
+        endcode endcond enddocbookonly enddot endhtmlonly endif endinternal endlatexonly endlink \
+        endmanonly endmsc endparblock endrtfonly endsecreflist endverbatim enduml endxmlonly \
+        enum example exception extends f$ f( f) f[ f] f{ f} file fileinfo fn groupgraph \
+        headerfile hidecallergraph hidecallgraph hidecollaborationgraph hidedirectorygraph \
+        hideenumvalues hidegroupgraph hideincludedbygraph hideincludegraph hideinheritancegraph \
+        hideinlinesource hiderefby hiderefs hideinitializer htmlinclude htmlonly idlexcept if \
+        ifnot iliteral endiliteral image implements important include includedoc includelineno \
+        includegraph includedbygraph ingroup inheritancegraph interface internal invariant \
+        latexinclude latexonly li line lineinfo link mainpage maninclude manonly memberof module \
+        msc mscfile n name namespace noop nosubgrouping note overload p package page par \
+        paragraph param parblock post pre private privatesection property protected \
+        protectedsection protocol public publicsection pure qualifier raisewarning ref refitem \
+        related relates relatedalso relatesalso remark remarks result return returns retval \
+        rtfinclude rtfonly sa secreflist section see short showdate showenumvalues \
+        showinitializer showinlinesource showrefby showrefs since skip skipline snippet \
+        snippetdoc snippetlineno startuml static struct subpage subsection subsubsection \
+        subparagraph subsubparagraph tableofcontents test throw throws todo tparam typedef \
+        plantumlfile union until var verbatim verbinclude version vhdlflow warning weakgroup \
+        xmlinclude xmlonly xrefitem $ @ \\ & ~ < = > # % \" . :: | -- ---";
+
+    #[test]
+    fn complete() {
+        for command in MANUAL.split_whitespace() {
+            assert!(
+                COMMANDS.iter().any(|v| v.contains_key(command)),
+                "`{command}` is missing"
+            );
+        }
+
+        let count = COMMANDS.iter().map(|v| v.len()).sum::<usize>();
+        assert_eq!(
+            count,
+            MANUAL.split_whitespace().count(),
+            "the table has commands that aren't in the manual"
+        );
+    }
+
+    #[test]
+    fn block_commands() {
+        assert!(is_block_command("param"));
+        assert!(is_block_command("xrefitem"));
+        assert!(is_block_command("name"));
+        assert!(is_block_command("important"));
+        assert!(!is_block_command("if"));
+        assert!(!is_block_command("li"));
+        assert!(!is_block_command("b"));
+        assert!(!is_block_command("thisdoesntexist"));
+    }
+}
@@ -0,0 +1,347 @@
+//! Extraction of the semantic content of a Doxygen comment.
+//!
+//! While [`generator::rustdoc`](crate::generator::rustdoc) creates Markdown, a [`DocComment`]
+//! holds each part of the comment (brief, parameters, return values...) on its own, which is
+//! useful to generate wrappers, validate documentation or produce reports.
+//!
+//! Descriptions are plain text: inline commands are replaced by their arguments (`@b word` becomes
+//! `word`), every line is trimmed and empty lines at the start and end are removed.
+//!
+//! # Examples
+//!
+//! ```
+//! use doxygen_rs::doc::{Direction, DocComment};
+//!
+//! let doc = DocComment::parse(
+//!     "@brief Adds two numbers\n@param[in] a First number\n@param b Second number\n@return The sum",
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(doc.brief.as_deref(), Some("Adds two numbers"));
+//! assert_eq!(doc.params[0].name, "a");
+//! assert_eq!(doc.params[0].direction, Some(Direction::In));
+//! assert_eq!(doc.params[1].description, "Second number");
+//! assert_eq!(doc.returns.as_deref(), Some("The sum"));
+//! ```
+
+use crate::ast::{Block, Comment, Inline};
+use crate::ParseError;
+
+/// The semantic content of a Doxygen comment.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct DocComment {
+    /// The content of `@brief`/`@short`.
+    pub brief: Option<String>,
+    /// Every paragraph that isn't part of any other section, including `@details`, `@par` and
+    /// the content of verbatim blocks like `@code`.
+    pub details: Vec<String>,
+    /// Every `@param`, in order.
+    pub params: Vec<Param>,
+    /// Every `@tparam`, in order.
+    pub tparams: Vec<TemplateParam>,
+    /// The content of `@return`/`@returns`/`@result`.
+    pub returns: Option<String>,
+    /// Every `@retval`, in order.
+    pub retvals: Vec<ReturnValue>,
+    /// Every `@throw`/`@throws`/`@exception`, in order.
+    pub throws: Vec<Exception>,
+    /// The references of every `@sa`/`@see`, in order. References separated by commas or spaces
+    /// are split, while free text is kept whole.
+    pub see_also: Vec<String>,
+    /// The content of `@deprecated`.
+    pub deprecated: Option<String>,
+    /// The content of `@since`.
+    pub since: Option<String>,
+    /// The content of every `@note`/`@remark`/`@remarks`, in order.
+    pub notes: Vec<String>,
+}
+
+/// The direction of a `@param`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Direction {
+    In,
+    Out,
+    InOut,
+}
+
+/// A function parameter, documented with `@param`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Param {
+    pub name: String,
+    /// The direction given with `@param[in]`, `@param[out]` or `@param[in,out]`, if any.
+    pub direction: Option<Direction>,
+    pub description: String,
+}
+
+/// A template parameter, documented with `@tparam`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct TemplateParam {
+    pub name: String,
+    pub description: String,
+}
+
+/// A specific return value, documented with `@retval`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ReturnValue {
+    pub value: String,
+    pub description: String,
+}
+
+/// An exception, documented with `@throw`, `@throws` or `@exception`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Exception {
+    pub exception: String,
+    pub description: String,
+}
+
+impl DocComment {
+    /// Parses a Doxygen comment and extracts its content.
+    ///
+    /// # Errors
+    ///
+    /// This function will error if the comment can't be parsed, see [`ParseError`].
+    pub fn parse(value: &str) -> Result<Self, ParseError> {
+        Ok(Self::from_ast(&crate::parse(value)?))
+    }
+
+    /// Extracts the content of an already parsed comment.
+    pub fn from_ast(comment: &Comment) -> Self {
+        let mut doc = DocComment::default();
+
+        for block in &comment.blocks {
+            let Block::Command {
+                command, content, ..
+            } = block
+            else {
+                match block {
+                    Block::Paragraph { content, .. } => {
+                        push_non_empty(&mut doc.details, plain_text(content))
+                    }
+                    Block::Verbatim { content, .. } => {
+                        push_non_empty(&mut doc.details, content.clone())
+                    }
+                    _ => {}
+                }
+                continue;
+            };
+
+            let argument = command.arguments.first().cloned().unwrap_or_default();
+            let description = plain_text(content);
+
+            match command.name.as_str() {
+                "brief" | "short" => append(&mut doc.brief, description),
+                "details" | "par" => push_non_empty(&mut doc.details, description),
+                "param" => doc.params.push(Param {
+                    name: argument,
+                    direction: direction(&command.meta),
+                    description,
+                }),
+                "tparam" => doc.tparams.push(TemplateParam {
+                    name: argument,
+                    description,
+                }),
+                "returns" | "return" | "result" => append(&mut doc.returns, description),
+                "retval" => doc.retvals.push(ReturnValue {
+                    value: argument,
+                    description,
+                }),
+                "throw" | "throws" | "exception" => doc.throws.push(Exception {
+                    exception: argument,
+                    description,
+                }),
+                "sa" | "see" => doc.see_also.extend(split_references(&description)),
+                "deprecated" => append(&mut doc.deprecated, description),
+                "since" => append(&mut doc.since, description),
+                "note" | "remark" | "remarks" => push_non_empty(&mut doc.notes, description),
+                _ => {}
+            }
+        }
+
+        doc
+    }
+}
+
+fn direction(meta: &[String]) -> Option<Direction> {
+    let is_in = meta.iter().any(|v| v == "in");
+    let is_out = meta.iter().any(|v| v == "out");
+
+    match (is_in, is_out) {
+        (true, true) => Some(Direction::InOut),
+        (true, false) => Some(Direction::In),
+        (false, true) => Some(Direction::Out),
+        (false, false) => None,
+    }
+}
+
+fn append(value: &mut Option<String>, text: String) {
+    match value {
+        Some(value) if !text.is_empty() => {
+            value.push('\n');
+            *value += &text;
+        }
+        Some(_) => {}
+        None => *value = Some(text),
+    }
+}
+
+fn push_non_empty(values: &mut Vec<String>, text: String) {
+    if !text.is_empty() {
+        values.push(text);
+    }
+}
+
+pub(crate) fn plain_text(content: &[Inline]) -> String {
+    let mut text = String::new();
+
+    for inline in content {
+        match inline {
+            Inline::Text { text: value, .. } => text += value,
+            Inline::Command(command) => match (command.name.as_str(), &command.arguments[..]) {
+                // The text shown for the reference, when it has one
+                ("ref", [_, shown]) => text += shown,
+                // The text between both commands is already part of the content
+                ("link" | "endlink", _) => {}
+                ("image", [_, _, caption]) => text += caption,
+                ("xrefitem", [_, heading, ..]) => text += heading,
+                ("anchor" | "image" | "xrefitem", _) => {}
+                _ => text += &command.arguments.join(" "),
+            },
+            Inline::ListItem { .. } => text += "- ",
+            Inline::ListEnd { .. } => {}
+        }
+    }
+
+    text.lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Splits the content of `@sa`/`@see` into its references.
+///
+/// The references can be separated by commas or, when every word looks like a symbol (with a `_`,
+/// `::`, `(`, `#`, `.`, a digit or an uppercase letter), by spaces. Anything else is free text,
+/// which is kept whole.
+pub(crate) fn split_references(text: &str) -> Vec<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    // The period ending the list, not part of the last reference
+    let references = text.strip_suffix('.').unwrap_or(&text);
+
+    if references.contains(',') {
+        references
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+            .collect()
+    } else if !references.contains('[') && references.split(' ').all(is_symbol) {
+        references.split(' ').map(String::from).collect()
+    } else if text.is_empty() {
+        vec![]
+    } else {
+        vec![text]
+    }
+}
+
+fn is_symbol(word: &str) -> bool {
+    word.chars().any(|v| v.is_ascii_digit() || v.is_uppercase())
+        || ["_", "::", "(", "#", "."].iter().any(|v| word.contains(v))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sections() {
+        let doc = DocComment::parse(
+            "@brief Does things.\nThis is a detail.\n\nAnother @b detail.\n@param[in,out] value The value.\n@retval 0 Success.\n@retval -1 Failure.\n@throws std::bad_alloc When out of memory.\n@sa other_thing\n@see another_thing\n@deprecated Use other_thing.\n@since 1.0\n@note A note\nin two lines.\n@remark A remark.",
+        )
+        .unwrap();
+
+        assert_eq!(
+            doc,
+            DocComment {
+                brief: Some("Does things.\nThis is a detail.".into()),
+                details: vec!["Another detail.".into()],
+                params: vec![Param {
+                    name: "value".into(),
+                    direction: Some(Direction::InOut),
+                    description: "The value.".into(),
+                }],
+                tparams: vec![],
+                returns: None,
+                retvals: vec![
+                    ReturnValue {
+                        value: "0".into(),
+                        description: "Success.".into(),
+                    },
+                    ReturnValue {
+                        value: "-1".into(),
+                        description: "Failure.".into(),
+                    }
+                ],
+                throws: vec![Exception {
+                    exception: "std::bad_alloc".into(),
+                    description: "When out of memory.".into(),
+                }],
+                see_also: vec!["other_thing".into(), "another_thing".into()],
+                deprecated: Some("Use other_thing.".into()),
+                since: Some("1.0".into()),
+                notes: vec!["A note\nin two lines.".into(), "A remark.".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn template_params() {
+        let doc = DocComment::parse("@tparam T The type.\n@tparam N The size.").unwrap();
+        assert_eq!(
+            doc.tparams,
+            vec![
+                TemplateParam {
+                    name: "T".into(),
+                    description: "The type.".into(),
+                },
+                TemplateParam {
+                    name: "N".into(),
+                    description: "The size.".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn references() {
+        assert_eq!(split_references("a_fn, b_fn."), vec!["a_fn", "b_fn"]);
+        assert_eq!(
+            split_references("Foo::bar\n  baz()"),
+            vec!["Foo::bar", "baz()"]
+        );
+        assert_eq!(
+            split_references("the memory docs."),
+            vec!["the memory docs."]
+        );
+        assert!(split_references("").is_empty());
+    }
+
+    #[test]
+    fn repeated_returns() {
+        let doc = DocComment::parse("@return The value\n@returns or nothing.").unwrap();
+        assert_eq!(doc.returns.as_deref(), Some("The value\nor nothing."));
+    }
+
+    #[test]
+    fn can_extract_example() {
+        let example = include_str!("../tests/assets/example-bindgen.rs");
+        let doc = DocComment::parse(example).unwrap();
+        assert_eq!(doc.params.len(), 6);
+    }
+}
@@ -1,102 +1,835 @@
+use std::collections::HashSet;
+
+use crate::commands::is_block_command;
+use crate::doc::split_references;
 use crate::emojis;
+use crate::lexer::{Span, Spanned};
+use crate::normalize::normalize;
 use crate::parser::{parse, GrammarItem, ParseError};
+use crate::{Admonition, NoteStyle, ParamStyle, Section, SectionOrder, TransformOptions};
 
 /// Creates a Rustdoc string from a Doxygen string.
 ///
 /// # Errors
 ///
-/// This function can error if there are missing parts of a given Doxygen annotation (like `@param`
-/// missing the variable name)
+/// This function can error if there are missing parts of a given Doxygen annotation (like `@b`
+/// missing the word to style) or if `@emoji` is given an unknown emoji.
 pub fn rustdoc(input: String) -> Result<String, ParseError> {
+    rustdoc_with(input, &TransformOptions::default())
+}
+
+/// Creates a Rustdoc string from a Doxygen string, using the given [`TransformOptions`].
+///
+/// # Errors
+///
+/// See [`rustdoc`].
+pub fn rustdoc_with(input: String, options: &TransformOptions) -> Result<String, ParseError> {
+    generate(normalize(&input), options)
+}
+
+/// Creates a Rustdoc string from a Doxygen string whose comment decorations were already removed.
+pub(crate) fn generate(input: String, options: &TransformOptions) -> Result<String, ParseError> {
     let parsed = parse(input)?;
     let mut result = String::new();
-    let mut already_added_params = false;
-    let mut already_added_returns = false;
-    let mut already_added_throws = false;
-    let mut group_started = false;
+    let mut added_sections = HashSet::new();
+    // The position in `result` where the text of an open `@link` starts, and its target
+    let mut link: Option<(usize, String)> = None;
+    let mut hidden_command = false;
+    // Whether the current paragraph is part of the block of an admonition
+    let mut quoting = false;
+    // Where the current paragraph is collected into, along with the rest of the result
+    let mut collecting: Option<(Target, String)> = None;
+    let mut collected: Vec<(Target, Vec<String>)> = vec![];
+    let mut lists = Lists::default();
+    // The command of the last note, to join the ones that follow it
+    let mut last_note: Option<String> = None;
+    let mut autobrief = if options.autobrief {
+        Autobrief::Searching
+    } else {
+        Autobrief::Done
+    };
+
+    let canonical = options.section_order == SectionOrder::Canonical;
+    if canonical {
+        // The headings are added once every section is collected
+        added_sections.extend(CANONICAL_ORDER);
+    }
 
-    for item in parsed {
-        result += &match item {
+    let ends_with_list = matches!(
+        parsed.last(),
+        Some(Spanned {
+            item: GrammarItem::ListEnd { .. },
+            ..
+        })
+    );
+    for Spanned { mut item, span } in parsed {
+        split_summary(&mut item, &mut result, &mut autobrief);
+
+        // A `@link` without `@endlink` ends with its paragraph
+        if link.is_some() && !continues_paragraph(&item, at_line_start(&result)) {
+            flush_link(&mut result, &mut link, options);
+        }
+
+        if collecting.is_some() {
+            let line_start = result.rfind('\n').map_or(0, |v| v + 1);
+            let new_line = result[line_start..].trim().is_empty();
+            if !continues_paragraph(&item, new_line) {
+                flush_link(&mut result, &mut link, options);
+                finish_collecting(&mut result, &mut collecting, &mut collected);
+            }
+        }
+
+        if quoting {
+            let line_start = result.rfind('\n').map_or(0, |v| v + 1);
+            let new_line = result[line_start..].trim().is_empty();
+            quoting = continues_paragraph(&item, new_line);
+
+            if quoting && new_line {
+                result.insert_str(line_start, "> ");
+            }
+        }
+
+        let output = match item {
+            GrammarItem::Notation { tag, params, .. } if tag == "link" => {
+                match params.into_iter().next() {
+                    Some(target) => link = Some((result.len(), target)),
+                    None if !options.strict => {}
+                    None => return Err(ParseError::MissingArgument { command: tag, span }),
+                }
+                continue;
+            }
+            // `@endlink.`: the punctuation is part of the command word
+            GrammarItem::Notation { tag, .. } if tag.starts_with("endlink") => {
+                let punctuation = &tag["endlink".len()..];
+                if let Some((start, target)) = link.take() {
+                    close_link(&mut result, start, &target, options);
+                }
+                if !punctuation.is_empty() {
+                    result.truncate(result.trim_end().len());
+                    result += punctuation;
+                }
+                continue;
+            }
             GrammarItem::Notation { meta, params, tag } => {
-                let (str, (added_param, added_return, added_throws)) = generate_notation(
-                    tag,
-                    meta,
-                    params,
-                    (
-                        already_added_params,
-                        already_added_returns,
-                        already_added_throws,
-                    ),
+                if is_block_command(&tag) {
+                    lists.clear();
+                }
+
+                let collect = collected_paragraph(&tag, &params, options);
+                if let Some(Collect { target, bullet, .. }) = collect {
+                    flush_link(&mut result, &mut link, options);
+                    finish_collecting(&mut result, &mut collecting, &mut collected);
+                    collecting = Some((target, std::mem::take(&mut result)));
+                    if bullet {
+                        result += "* ";
+                    }
+                }
+
+                let section_item = matches!(
+                    tag.as_str(),
+                    "param" | "tparam" | "retval" | "throw" | "throws" | "exception"
                 );
-                if added_param {
-                    already_added_params = true;
+                // The whole paragraph of a note is part of its quote
+                let quoted = is_note_command(&tag)
+                    && options.note_style == NoteStyle::Quote
+                    && collect.is_none();
+                let mut joined = false;
+                if quoted {
+                    start_note(&mut result);
+
+                    // Like Doxygen, adjacent notes of the same kind are joined, while other ones
+                    // are a separate quote. A blank line ends the quote, so it's never joined
+                    let previous_line = result
+                        .strip_suffix('\n')
+                        .unwrap_or(&result)
+                        .rsplit('\n')
+                        .next()
+                        .unwrap_or_default();
+                    if previous_line.starts_with('>') {
+                        joined = last_note.as_deref() == Some(tag.as_str());
+                        if !joined {
+                            result.push('\n');
+                        }
+                    }
+                    last_note = Some(tag.clone());
                 }
 
-                if added_return {
-                    already_added_returns = true;
+                let str = match collect {
+                    _ if joined => options.note(""),
+                    Some(Collect {
+                        keep_command: false,
+                        ..
+                    }) => String::new(),
+                    _ => generate_notation(tag, meta, params, span, options, &mut added_sections)?,
+                };
+                if str.starts_with('#') {
+                    start_heading(&mut result);
                 }
 
-                if added_throws {
-                    already_added_throws = true;
+                // The description of a section item continues on the lines that follow
+                if (section_item && !str.is_empty()) || collect.is_some_and(|v| v.bullet) {
+                    lists.section_item();
                 }
 
+                // Commands that aren't shown (like `@anchor`) shouldn't leave two spaces behind
+                hidden_command = str.is_empty();
+                quoting = quoting || quoted;
                 str
             }
-            GrammarItem::Text(v) => if group_started {
-                v.replacen("*", "", 1)
-            } else {
-                v
-            },
+            GrammarItem::Text(v) if collecting.is_some() && blank_line(&v).is_some() => {
+                let (paragraph, rest) = v.split_at(blank_line(&v).unwrap());
+                let paragraph = &indent_list_lines(paragraph, &mut lists, at_line_start(&result));
+                lists.clear();
+                if hidden_command && (result.is_empty() || result.ends_with(' ')) {
+                    result += paragraph.strip_prefix(' ').unwrap_or(paragraph);
+                } else {
+                    result += paragraph;
+                }
+                hidden_command = false;
+                flush_link(&mut result, &mut link, options);
+                finish_collecting(&mut result, &mut collecting, &mut collected);
+
+                // The blank line still ends the paragraph the command was in
+                if !result.is_empty() && !result.ends_with('\n') {
+                    result.truncate(result.trim_end().len());
+                    result.push('\n');
+                }
+                rest.to_string()
+            }
+            GrammarItem::Text(v)
+                if hidden_command && (result.is_empty() || result.ends_with(' ')) =>
+            {
+                hidden_command = false;
+                let v = v.strip_prefix(' ').unwrap_or(&v);
+                indent_list_lines(v, &mut lists, at_line_start(&result))
+            }
+            GrammarItem::Text(v) => {
+                hidden_command = false;
+                let v = indent_list_lines(&v, &mut lists, at_line_start(&result));
+                if quoting {
+                    quote_lines(&v, &mut quoting)
+                } else {
+                    v
+                }
+            }
+            GrammarItem::ListItem { depth, ordered } => {
+                hidden_command = false;
+                let marker = if ordered { "1. " } else { "* " };
+                let indentation = lists.item(depth, marker.len());
+
+                // Every item starts its own line
+                let prefix = if quoting { "> " } else { "" };
+                let line_start = result.rfind('\n').map_or(0, |v| v + 1);
+                let line = &result[line_start..];
+                if line.strip_prefix(prefix).unwrap_or(line).trim().is_empty() {
+                    result.truncate(line_start);
+                } else {
+                    result.push('\n');
+                }
+
+                format!("{prefix}{}{marker}", " ".repeat(indentation))
+            }
+            GrammarItem::ListEnd { depth } => {
+                lists.end(depth);
+                result.truncate(result.trim_end().len());
+
+                // The text that follows is a new paragraph (of the item of the outer list, if
+                // any) instead of a continuation of the last item
+                let indentation = " ".repeat(lists.indentation().unwrap_or_default());
+                if quoting {
+                    format!("\n>\n{indentation}")
+                } else {
+                    format!("\n\n{indentation}")
+                }
+            }
             // See <https://stackoverflow.com/a/40354789>
             GrammarItem::GroupStart => {
-                group_started = true;
-                String::from("# ")
-            },
-            GrammarItem::GroupEnd => {
-                group_started = false;
-                continue
-            },
+                lists.clear();
+                start_heading(&mut result);
+                options.heading("")
+            }
+            GrammarItem::GroupEnd => continue,
+            GrammarItem::Verbatim {
+                command,
+                language,
+                content,
+            } => {
+                lists.clear();
+                let language = match command.as_str() {
+                    "verbatim" => "text".into(),
+                    _ => language.unwrap_or_else(|| options.code_block_language.clone()),
+                };
+
+                let mut str = if result.is_empty() || result.ends_with('\n') {
+                    String::new()
+                } else {
+                    "\n".into()
+                };
+
+                str += &format!("```{language}\n{content}\n```\n");
+                str
+            }
         };
+        result += &output;
+
+        if let Some(blank) = link
+            .as_ref()
+            .and_then(|(start, _)| blank_line(&result[*start..]).map(|blank| start + blank))
+        {
+            let rest = result.split_off(blank);
+            flush_link(&mut result, &mut link, options);
+            result += &rest;
+        }
+    }
+
+    flush_link(&mut result, &mut link, options);
+
+    if ends_with_list {
+        result.truncate(result.trim_end().len());
+    }
+
+    finish_collecting(&mut result, &mut collecting, &mut collected);
+    let mut summary = vec![];
+    let mut sections: Vec<(Section, Vec<String>)> = vec![];
+    for (target, items) in collected {
+        match target {
+            Target::Summary => summary.extend(items),
+            Target::Section(section) => sections.push((section, items)),
+        }
+    }
+
+    if canonical {
+        sections.sort_by_key(|(section, _)| CANONICAL_ORDER.iter().position(|v| v == section));
+
+        let details = result.trim();
+        result = match (summary.is_empty(), details.is_empty()) {
+            (false, false) => format!("{}\n\n{details}", summary.join("\n")),
+            (false, true) => summary.join("\n"),
+            (true, _) => details.to_string(),
+        };
+    }
+
+    // See also is always the last section
+    sections.sort_by_key(|(section, _)| *section == Section::SeeAlso);
+    for (section, mut items) in sections {
+        if section == Section::SeeAlso {
+            items = items
+                .iter()
+                .flat_map(|v| split_references(v))
+                .map(|v| format!("* {}", see_also_item(&v, options)))
+                .collect();
+        }
+
+        if !result.is_empty() && !result.ends_with('\n') {
+            result.push('\n');
+        }
+
+        result += &options.section_heading(section);
+        result += &items.join("\n");
+    }
+
+    Ok(fence_indented_code(&result))
+}
+
+/// The order of the sections with [`SectionOrder::Canonical`].
+const CANONICAL_ORDER: [Section; 10] = [
+    Section::Arguments,
+    Section::GenericParameters,
+    Section::Returns,
+    Section::Errors,
+    Section::Safety,
+    Section::Preconditions,
+    Section::Postconditions,
+    Section::Throws,
+    Section::Notes,
+    Section::SeeAlso,
+];
+
+/// The search of the first sentence of a comment with [`TransformOptions::autobrief`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Autobrief {
+    Searching,
+    /// The sentence ended at the end of the last text, so the next item starts the details.
+    Ended,
+    Done,
+}
+
+/// Separates the first sentence of a comment from the rest of its first paragraph, so it's the
+/// summary of the item.
+fn split_summary(item: &mut GrammarItem, result: &mut String, autobrief: &mut Autobrief) {
+    let start_details = |result: &mut String| {
+        result.truncate(result.trim_end().len());
+        *result += "\n\n";
+        Autobrief::Done
+    };
+
+    match (*autobrief, item) {
+        (Autobrief::Done, _) => {}
+        // The comment has its own summary, or the first paragraph ended without a period
+        (_, GrammarItem::Notation { tag, .. }) if is_block_command(tag) => {
+            *autobrief = Autobrief::Done
+        }
+        (Autobrief::Searching, GrammarItem::Text(text)) => {
+            let end = text
+                .match_indices('.')
+                .map(|(index, _)| index + 1)
+                .find(|&end| ends_sentence(text, end));
+
+            match (end, blank_line(text)) {
+                (Some(end), blank) if blank.map_or(true, |blank| end < blank) => {
+                    let rest = text[end..].trim_start();
+                    if rest.is_empty() {
+                        *autobrief = Autobrief::Ended;
+                    } else {
+                        *text = format!("{}\n\n{rest}", &text[..end]);
+                        *autobrief = Autobrief::Done;
+                    }
+                }
+                (_, Some(_)) => *autobrief = Autobrief::Done,
+                _ => {}
+            }
+        }
+        // Like `@b word.`
+        (Autobrief::Searching, GrammarItem::Notation { params, .. })
+            if params.last().is_some_and(|v| v.ends_with('.')) =>
+        {
+            *autobrief = Autobrief::Ended
+        }
+        (Autobrief::Searching, GrammarItem::Notation { .. }) => {}
+        (Autobrief::Ended, GrammarItem::Text(text)) if !text.trim().is_empty() => {
+            *text = text.trim_start().into();
+            *autobrief = start_details(result);
+        }
+        (Autobrief::Ended, GrammarItem::Text(_)) => {}
+        (Autobrief::Ended, GrammarItem::Notation { .. }) => *autobrief = start_details(result),
+        _ => *autobrief = Autobrief::Done,
+    }
+}
+
+/// Returns whether the period before `end` ends a sentence: it's followed by a space or the end of
+/// the line, the next word doesn't start in lowercase, and it isn't part of an abbreviation like
+/// `e.g.`.
+fn ends_sentence(text: &str, end: usize) -> bool {
+    let rest = &text[end..];
+    let word = text[..end - 1]
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or_default();
+
+    rest.starts_with(char::is_whitespace)
+        && !rest.trim_start().starts_with(char::is_lowercase)
+        && !word.contains('.')
+}
+
+/// Where the paragraph of a command is collected into.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Target {
+    /// The summary before the details, from `@brief`.
+    Summary,
+    Section(Section),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Collect {
+    target: Target,
+    /// Whether the paragraph is a list item whose bullet isn't added by the command.
+    bullet: bool,
+    /// Whether the output of the command is kept at the start of the paragraph.
+    keep_command: bool,
+}
+
+/// The lists open in the current paragraph.
+#[derive(Debug, Default)]
+struct Lists {
+    /// The column where the text of the items of every open list starts.
+    columns: Vec<usize>,
+    /// Whether the paragraph is an item of a section (like `@param`), which the lists written in
+    /// the comment are nested in.
+    section_item: bool,
+}
+
+impl Lists {
+    fn clear(&mut self) {
+        self.columns.clear();
+        self.section_item = false;
+    }
+
+    /// Starts the paragraph of an item of a section, which uses `* ` as its bullet.
+    fn section_item(&mut self) {
+        self.columns = vec![2];
+        self.section_item = true;
+    }
+
+    /// Starts an item of the list nested in `depth` others, returning its indentation.
+    fn item(&mut self, depth: usize, marker_width: usize) -> usize {
+        self.end(depth);
+        let indentation = self.indentation().unwrap_or_default();
+        self.columns.push(indentation + marker_width);
+        indentation
+    }
+
+    /// Ends the lists nested deeper than `depth`.
+    fn end(&mut self, depth: usize) {
+        self.columns
+            .truncate(depth + usize::from(self.section_item));
+    }
+
+    /// Returns the indentation of the lines that continue the innermost item, if any.
+    fn indentation(&self) -> Option<usize> {
+        self.columns.last().copied()
+    }
+}
+
+/// Returns where the paragraph of `tag` is collected into, if it isn't added where it's written.
+fn collected_paragraph(
+    tag: &str,
+    params: &[String],
+    options: &TransformOptions,
+) -> Option<Collect> {
+    let collect = |target, bullet, keep_command| {
+        Some(Collect {
+            target,
+            bullet,
+            keep_command,
+        })
+    };
+
+    if let Some(section) = options.collected_section(tag) {
+        return collect(Target::Section(section), true, false);
+    }
+
+    // The references are split into items once the whole section is collected
+    if matches!(tag, "sa" | "see") {
+        return collect(Target::Section(Section::SeeAlso), false, false);
+    }
+
+    if options.section_order != SectionOrder::Canonical {
+        return None;
+    }
+
+    let section = match tag {
+        "brief" | "short" => return collect(Target::Summary, false, true),
+        "note" | "remark" | "remarks" => {
+            return collect(Target::Section(Section::Notes), true, false)
+        }
+        "param" => Section::Arguments,
+        "tparam" => Section::GenericParameters,
+        "retval" if params.first().is_some_and(|v| options.is_error_retval(v)) => Section::Errors,
+        "retval" | "return" | "returns" | "result" => Section::Returns,
+        "throw" | "throws" | "exception" => Section::Throws,
+        _ => return None,
+    };
+
+    collect(Target::Section(section), false, true)
+}
+
+/// Adds the paragraph being collected as an item of its section, and restores the rest of the
+/// result.
+fn finish_collecting(
+    result: &mut String,
+    collecting: &mut Option<(Target, String)>,
+    collected: &mut Vec<(Target, Vec<String>)>,
+) {
+    let Some((target, rest)) = collecting.take() else {
+        return;
+    };
+
+    let paragraph = std::mem::replace(result, rest);
+    let paragraph = paragraph.trim();
+    if paragraph.is_empty() || paragraph == "*" {
+        return;
     }
 
-    Ok(result)
+    // Continuation lines are already indented to stay part of their item
+    let item = paragraph
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    match collected.iter_mut().find(|(v, _)| *v == target) {
+        Some((_, items)) => items.push(item),
+        None => collected.push((target, vec![item])),
+    }
+}
+
+/// Links a reference of `@sa`, unless it's free text or already a link. URLs are autolinks, since
+/// rustdoc would take them for broken intra-doc links.
+fn see_also_item(reference: &str, options: &TransformOptions) -> String {
+    if reference.contains(char::is_whitespace) || reference.starts_with(['[', '`', '<']) {
+        return reference.into();
+    }
+
+    // The punctuation of the sentence isn't part of the reference
+    let reference = reference.trim_end_matches(['.', ',', ';', ':']);
+    if reference.contains("://") {
+        format!("<{reference}>")
+    } else if is_identifier(reference) {
+        options.link(reference)
+    } else {
+        reference.into()
+    }
+}
+
+/// Returns whether `reference` looks like the name of a symbol, like `svcMap`, `MemOp::MAP`,
+/// `Class#method` or `function()`.
+fn is_identifier(reference: &str) -> bool {
+    reference.starts_with(|c: char| c.is_alphabetic() || matches!(c, '_' | '~' | ':'))
+        && reference
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '~' | ':' | '.' | '#' | '(' | ')'))
+}
+
+/// Starts the line of a note, which is never in the middle of a paragraph.
+fn start_note(result: &mut String) {
+    if at_line_start(result) {
+        result.truncate(result.rfind('\n').map_or(0, |v| v + 1));
+    } else {
+        result.truncate(result.trim_end().len());
+        result.push('\n');
+    }
+}
+
+/// Ends the current line, since a Markdown heading only works at the start of one.
+fn start_heading(result: &mut String) {
+    if !result.is_empty() && !result.ends_with('\n') {
+        result.truncate(result.trim_end().len());
+        result.push('\n');
+    }
+}
+
+/// Returns whether `tag` is shown as a note, whose paragraph is quoted with [`NoteStyle::Quote`].
+fn is_note_command(tag: &str) -> bool {
+    Admonition::from_command(tag).is_some()
+        || matches!(
+            tag,
+            "note" | "remark" | "remarks" | "since" | "deprecated" | "xrefitem"
+        )
+}
+
+/// Returns the position of the first blank line of `text`.
+fn blank_line(text: &str) -> Option<usize> {
+    let mut position = text.find('\n')? + 1;
+
+    loop {
+        let rest = &text[position..];
+        let end = rest.find('\n')?;
+        if rest[..end].trim().is_empty() {
+            return Some(position);
+        }
+
+        position += end + 1;
+    }
+}
+
+/// Returns whether `item` continues the current paragraph, `new_line` being whether it's at the
+/// start of a line.
+fn continues_paragraph(item: &GrammarItem, new_line: bool) -> bool {
+    match item {
+        GrammarItem::Notation { tag, .. } => !is_block_command(tag),
+        GrammarItem::ListItem { .. } | GrammarItem::ListEnd { .. } => true,
+        GrammarItem::Text(v) => !(new_line && v.trim_start_matches([' ', '\t']).starts_with('\n')),
+        _ => false,
+    }
+}
+
+/// Indents the lines of `text` that continue the innermost item of `lists`, which are closed by a
+/// blank line. When `line_start` is set, the first line is already indented.
+fn indent_list_lines(text: &str, lists: &mut Lists, line_start: bool) -> String {
+    let mut lines = text.split('\n');
+    let first = lines.next().unwrap_or_default();
+    let mut result = if line_start && lists.indentation().is_some() {
+        first.trim_start().to_string()
+    } else {
+        first.to_string()
+    };
+    let mut lines = lines.peekable();
+
+    while let Some(line) = lines.next() {
+        result.push('\n');
+
+        if line.trim().is_empty() {
+            if lines.peek().is_some() {
+                lists.clear();
+            }
+            result += line;
+        } else if let Some(indentation) = lists.indentation() {
+            result += &" ".repeat(indentation);
+            result += line.trim_start();
+        } else {
+            result += line;
+        }
+    }
+
+    result
+}
+
+/// Returns whether nothing but whitespace (or the `>` of a quote) was written on the last line of
+/// `result`.
+fn at_line_start(result: &str) -> bool {
+    let line = &result[result.rfind('\n').map_or(0, |v| v + 1)..];
+    line.trim_start().trim_start_matches('>').trim().is_empty()
+}
+
+/// Quotes the lines of `text` until the end of the paragraph, after which `quoting` is cleared.
+/// Its first line continues an already quoted one, and a last line with only whitespace is left
+/// for the next item to quote.
+fn quote_lines(text: &str, quoting: &mut bool) -> String {
+    let mut lines = text.split('\n');
+    let mut result = lines.next().unwrap_or_default().to_string();
+    let mut lines = lines.peekable();
+
+    while let Some(line) = lines.next() {
+        result.push('\n');
+        let last = lines.peek().is_none();
+
+        if *quoting && line.trim().is_empty() && !last {
+            *quoting = false;
+        } else if *quoting && !line.trim().is_empty() {
+            result += "> ";
+        }
+        result += line;
+    }
+
+    result
+}
+
+/// Closes the `@link` that is still open, if any. This must be done before the paragraph in
+/// `result` is swapped for another one, since the start of the link is a position in it.
+fn flush_link(result: &mut String, link: &mut Option<(usize, String)>, options: &TransformOptions) {
+    if let Some((start, target)) = link.take() {
+        close_link(result, start, &target, options);
+    }
+}
+
+/// Turns the text written since a `@link` into a link to `target`. The whitespace after the
+/// text is kept, since the one after `@endlink` is dropped.
+fn close_link(result: &mut String, start: usize, target: &str, options: &TransformOptions) {
+    let text = result.split_off(start);
+    let trailing = &text[text.trim_end().len()..];
+
+    *result += &if text.trim().is_empty() {
+        options.link(target)
+    } else {
+        options.link_text(target, text.trim())
+    };
+    *result += trailing;
+}
+
+/// Wraps the runs of lines indented by four or more columns, which Markdown treats as code
+/// blocks (and rustdoc as doctests), in a `text` code block.
+///
+/// Lines inside code blocks or continuing a list item are left untouched.
+fn fence_indented_code(input: &str) -> String {
+    let lines = input.split('\n').collect::<Vec<_>>();
+    let mut result: Vec<String> = vec![];
+    let mut in_fence = false;
+    let mut in_list = false;
+    let mut previous_blank = true;
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        let is_code = |line: &str| !line.trim().is_empty() && indentation(line) >= 4;
+
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        } else if !in_fence && !in_list && previous_blank && is_code(line) {
+            let start = index;
+            while index < lines.len() && is_code(lines[index]) {
+                index += 1;
+            }
+
+            let block = &lines[start..index];
+            let dedent = block
+                .iter()
+                .map(|v| indentation(v))
+                .min()
+                .unwrap_or_default();
+
+            result.push("```text".into());
+            result.extend(block.iter().map(|v| expand_tabs(v)[dedent..].to_string()));
+            result.push("```".into());
+            previous_blank = false;
+            continue;
+        } else if !line.trim().is_empty() {
+            let trimmed = line.trim_start();
+            let is_list_item = ["* ", "- ", "+ "].iter().any(|v| trimmed.starts_with(v))
+                || trimmed
+                    .split_once(". ")
+                    .is_some_and(|(number, _)| number.parse::<usize>().is_ok());
+
+            in_list = is_list_item || (in_list && indentation(line) > 0);
+        }
+
+        previous_blank = line.trim().is_empty();
+        result.push(line.into());
+        index += 1;
+    }
+
+    result.join("\n")
+}
+
+/// Returns the width of the leading whitespace of `line`, with tabs stopping every four columns.
+fn indentation(line: &str) -> usize {
+    let line = expand_tabs(line);
+    line.len() - line.trim_start().len()
+}
+
+fn expand_tabs(line: &str) -> String {
+    let mut result = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next_if(|c| c.is_whitespace()) {
+        if c == '\t' {
+            result += &" ".repeat(4 - result.len() % 4);
+        } else {
+            result.push(' ');
+        }
+    }
+
+    result.extend(chars);
+    result
 }
 
 fn generate_notation(
     tag: String,
     meta: Vec<String>,
     params: Vec<String>,
-    (already_params, already_returns, already_throws): (bool, bool, bool),
-) -> (String, (bool, bool, bool)) {
-    let mut new_param = false;
-    let mut new_return = false;
-    let mut new_throw = false;
-
-    (
-        match tag.as_str() {
+    span: Span,
+    options: &TransformOptions,
+    added_sections: &mut HashSet<Section>,
+) -> Result<String, ParseError> {
+    // The heading of a section is only added before its first item
+    let mut section_heading = |section| {
+        if added_sections.insert(section) {
+            options.section_heading(section)
+        } else {
+            String::new()
+        }
+    };
+
+    let argument = || {
+        params.first().ok_or_else(|| ParseError::MissingArgument {
+            command: tag.clone(),
+            span,
+        })
+    };
+
+    let mut generate = || -> Result<String, ParseError> {
+        Ok(match tag.as_str() {
             "param" => {
-                let param = params.get(0);
-                new_param = true;
-                let mut str = if !already_params {
-                    "# Arguments\n\n".into()
-                } else {
-                    String::new()
-                };
+                let param = params.first();
+                let mut str = section_heading(Section::Arguments);
 
                 str += &if let Some(param) = param {
-                    if meta.is_empty() {
-                        format!("* `{param}` -")
-                    } else {
-                        if let Some(second) = meta.get(1) {
-                            format!(
-                                "* `{}` (direction {}, {}) -",
-                                param,
-                                meta.get(0).unwrap(),
-                                second
-                            )
-                        } else {
-                            format!("* `{}` (direction {}) -", param, meta.get(0).unwrap())
+                    match (options.param_style, meta.is_empty()) {
+                        (ParamStyle::Direction, false) => {
+                            format!("* `{param}` (direction {}) -", meta.join(", "))
+                        }
+                        (ParamStyle::Brackets, false) => {
+                            format!("* `{param}` \\[{}\\] -", meta.join(", "))
                         }
+                        _ => format!("* `{param}` -"),
                     }
                 } else {
                     String::new()
@@ -104,82 +837,127 @@ fn generate_notation(
 
                 str
             }
+            "tparam" => {
+                let name = argument()?;
+                let mut str = section_heading(Section::GenericParameters);
+
+                str += &format!("* `{name}` -");
+                str
+            }
+            "image" => match &params[..] {
+                // Only the HTML images can be shown by rustdoc
+                [format, file, caption @ ..] if format == "html" => {
+                    format!("![{}]({file})", caption.first().map_or("", String::as_str))
+                }
+                [_, _, ..] => String::new(),
+                _ => {
+                    return Err(ParseError::MissingArgument {
+                        command: tag.clone(),
+                        span,
+                    })
+                }
+            },
+            "xrefitem" => match params.get(1) {
+                // The text that follows starts with a space
+                Some(heading) => options.note(&format!("**{heading}:**")),
+                None => {
+                    return Err(ParseError::MissingArgument {
+                        command: tag.clone(),
+                        span,
+                    })
+                }
+            },
             "a" | "e" | "em" => {
-                let word = params
-                    .get(0)
-                    .expect("@a/@e/@em doesn't contain a word to style");
+                let word = argument()?;
                 format!("_{word}_")
             }
             "b" => {
-                let word = params.get(0).expect("@b doesn't contain a word to style");
+                let word = argument()?;
                 format!("**{word}**")
             }
             "c" | "p" => {
-                let word = params
-                    .get(0)
-                    .expect("@c/@p doesn't contain a word to style");
+                let word = argument()?;
                 format!("`{word}`")
             }
             "emoji" => {
-                let word = params.get(0).expect("@emoji doesn't contain an emoji");
+                let word = argument()?.replace(':', "");
+                if !options.emojis {
+                    return Ok(format!(":{word}:"));
+                }
+
                 emojis::EMOJIS
-                    .get(&word.replace(':', ""))
-                    .expect("invalid emoji")
+                    .get(&word)
+                    .ok_or_else(|| ParseError::UnknownEmoji {
+                        emoji: word.clone(),
+                        span,
+                    })?
                     .to_string()
             }
-            "sa" | "see" => {
-                let code_ref = params.get(0).expect("@sa/@see doesn't contain a reference");
-                format!("[`{code_ref}`]")
+            "ref" => {
+                let name = argument()?;
+                let punctuation =
+                    name.len() - name.trim_end_matches(['.', ',', ';', ':', '!', '?']).len();
+                let (name, punctuation) = name.split_at(name.len() - punctuation);
+
+                match params.get(1) {
+                    Some(text) => options.link_text(name, text) + punctuation,
+                    None => options.link(name) + punctuation,
+                }
+            }
+            // The documentation can't be copied, so link to it instead
+            "copydoc" => {
+                let code_ref = argument()?;
+                format!("See {}.", options.link(code_ref))
             }
             "retval" => {
-                let var = params.get(0).expect("@retval doesn't contain a parameter");
-                new_return = true;
-                let mut str = if !already_returns {
-                    "# Returns\n\n".into()
+                let var = argument()?;
+                let mut str = if options.is_error_retval(var) {
+                    section_heading(Section::Errors)
                 } else {
-                    String::new()
+                    section_heading(Section::Returns)
                 };
 
                 str += &format!("* `{var}` -");
                 str
             }
-            "returns" | "return" | "result" => {
-                new_return = true;
-                if !already_returns {
-                    "# Returns\n\n".into()
-                } else {
-                    String::new()
-                }
-            }
+            "returns" | "return" | "result" => section_heading(Section::Returns),
             "throw" | "throws" | "exception" => {
-                new_throw = true;
-                let exception = params.get(0).expect("@param doesn't contain a parameter");
+                let exception = argument()?;
 
-                let mut str = if !already_throws {
-                    "# Throws\n\n".into()
-                } else {
-                    String::new()
-                };
+                let mut str = section_heading(Section::Throws);
 
-                str += &format!("* [`{exception}`] -");
+                str += &format!("* {} -", options.link(exception));
                 str
             }
-            "note" => String::from("> **Note:** "),
-            "since" => String::from("> Available since: "),
-            "deprecated" => String::from("> **Deprecated** "),
-            "remark" | "remarks" => String::from("> "),
-            "par" => String::from("# "),
+            "note" => options.note("**Note:** "),
+            "warning" | "attention" | "important" | "bug" | "todo" | "test" | "invariant" => {
+                options.admonition(Admonition::from_command(&tag).unwrap())
+            }
+            "since" => options.note("Available since: "),
+            "deprecated" => options.note("**Deprecated** "),
+            "remark" | "remarks" => options.note(""),
+            "par" => options.heading(""),
             "details" | "pre" | "post" => String::from("\n\n"),
             "brief" | "short" => String::new(),
             _ => String::new(),
-        },
-        (new_param, new_return, new_throw),
-    )
+        })
+    };
+
+    let str = match generate() {
+        Ok(str) => str,
+        Err(ParseError::MissingArgument { .. }) if !options.strict => String::new(),
+        Err(ParseError::UnknownEmoji { emoji, .. }) if !options.strict => format!(":{emoji}:"),
+        Err(err) => return Err(err),
+    };
+
+    Ok(str)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{LinkStyle, SafetyStyle, SectionOrder};
+    use std::collections::HashMap;
 
     macro_rules! test_rustdoc {
         ($input:literal, $expected:literal) => {
@@ -227,6 +1005,24 @@ mod test {
         );
     }
 
+    #[test]
+    fn multi_line_descriptions() {
+        test_rustdoc!(
+            "@param addr1    The virtual address.\n                 It has to be RW.\n@param size The size.\n\nDetails.",
+            "# Arguments\n\n* `addr1` - The virtual address.\n  It has to be RW.\n* `size` - The size.\n\nDetails."
+        );
+
+        test_rustdoc!(
+            "@retval 0 Success,\n  really.\n@throw std::bad_alloc When\nout of memory.\n@tparam T The\n  type.",
+            "# Returns\n\n* `0` - Success,\n  really.\n# Throws\n\n* [`std::bad_alloc`] - When\n  out of memory.\n# Generic Parameters\n\n* `T` - The\n  type."
+        );
+
+        test_rustdoc!(
+            "@param mode One of:\n - read\n   - nested\n - write\n .\n More about mode.\n@param b B",
+            "# Arguments\n\n* `mode` - One of:\n  * read\n    * nested\n  * write\n\n  More about mode.\n* `b` - B"
+        );
+    }
+
     #[test]
     fn italics() {
         test_rustdoc!(
@@ -269,11 +1065,203 @@ mod test {
         );
     }
 
+    #[test]
+    fn lists() {
+        test_rustdoc!(
+            "Modes:\n - read\n - write\n   - append\n   - truncate\n - execute\n\nDone.",
+            "Modes:\n* read\n* write\n  * append\n  * truncate\n* execute\n\nDone."
+        );
+
+        test_rustdoc!(
+            "Steps:\n -# First\n    continued\n -# Second\n   - nested\n   .\n   back in second\n -# Third\n .\nAfter the list.",
+            "Steps:\n1. First\n   continued\n1. Second\n   * nested\n\n   back in second\n1. Third\n\nAfter the list."
+        );
+
+        test_rustdoc!("Items:\n@li one\n@arg two", "Items:\n* one\n* two");
+        test_rustdoc!(
+            "@warning Careful:\n - a\n - b\n\nAfter",
+            "> **Warning:** Careful:\n> * a\n> * b\n\nAfter"
+        );
+        test_rustdoc!("a - b\n- c\n.", "a - b\n* c");
+    }
+
     #[test]
     fn see_also() {
         test_rustdoc!(
             "@sa random_thing @see random_thing_2",
-            "[`random_thing`] [`random_thing_2`]"
+            "# See also\n\n* [`random_thing`]\n* [`random_thing_2`]"
+        );
+        test_rustdoc!(
+            "Maps memory.\n@sa svcMapMemory, svcUnmapMemory\n@see @ref MemOp \"the operations\"\n@see The Memory chapter of the manual.\n\nMore details.\n@sa MEMOP_MAP MEMOP_FREE",
+            "Maps memory.\n\nMore details.\n# See also\n\n* [`svcMapMemory`]\n* [`svcUnmapMemory`]\n* [the operations](MemOp)\n* The Memory chapter of the manual.\n* [`MEMOP_MAP`]\n* [`MEMOP_FREE`]"
+        );
+        test_rustdoc!("@see qux.", "# See also\n\n* [`qux`]");
+        test_rustdoc!("@see foo, bar;", "# See also\n\n* [`foo`]\n* [`bar`]");
+        test_rustdoc!(
+            "See also @sa foo\n\nNext paragraph",
+            "See also\n\nNext paragraph\n# See also\n\n* [`foo`]"
+        );
+        test_rustdoc!(
+            "@see https://example.com/page, foo-bar",
+            "# See also\n\n* <https://example.com/page>\n* foo-bar"
+        );
+    }
+
+    #[test]
+    fn references() {
+        test_rustdoc!(
+            "Uses @ref MemOp, see @ref MEMOP_MAP \"the map operation\".",
+            "Uses [`MemOp`], see [the map operation](MEMOP_MAP)."
+        );
+        test_rustdoc!(
+            "See @link svcMap the mapping function @endlink for details.",
+            "See [the mapping function](svcMap) for details."
+        );
+        test_rustdoc!("See @link svcMap @endlink.", "See [`svcMap`].");
+        test_rustdoc!(
+            "Unterminated @link svcMap link",
+            "Unterminated [link](svcMap)"
+        );
+        test_rustdoc!(
+            "@link foo text @param a b",
+            "[text](foo)\n# Arguments\n\n* `a` - b"
+        );
+        test_rustdoc!(
+            "@link foo text\n@param a b",
+            "[text](foo)\n# Arguments\n\n* `a` - b"
+        );
+        test_rustdoc!(
+            "See @link foo the text\n\nNext paragraph",
+            "See [the text](foo)\n\nNext paragraph"
+        );
+        test_rustdoc!(
+            "See @link foo the text\n@par Title\nNext",
+            "See [the text](foo)\n# Title\nNext"
+        );
+        // The paragraph of `@see` is collected apart from the one of the open link
+        test_rustdoc!(
+            "See @link foo @see bar",
+            "See [`foo`] \n# See also\n\n* [`bar`]"
+        );
+
+        let options = TransformOptions::new().link_style(LinkStyle::Code);
+        assert_eq!(
+            rustdoc_with("@ref MEMOP_MAP \"the map\" or @ref MemOp".into(), &options).unwrap(),
+            "the map or `MemOp`"
+        );
+    }
+
+    #[test]
+    fn symbol_resolver() {
+        let symbols = HashMap::from([
+            ("MEMOP_MAP".to_string(), "MemOp_MEMOP_MAP".to_string()),
+            ("u32".to_string(), "crate::u32_".to_string()),
+        ]);
+        let options = TransformOptions::new().symbol_resolver(symbols);
+
+        assert_eq!(
+            rustdoc_with(
+                "@ref MEMOP_MAP \"Map\" @sa MEMOP_MAP @see other @throw u32 On error".into(),
+                &options
+            )
+            .unwrap(),
+            "[Map](MemOp_MEMOP_MAP)\n# Throws\n\n* [`u32`](crate::u32_) - On error\n# See also\n\n* [`MEMOP_MAP`](MemOp_MEMOP_MAP)\n* [`other`]"
+        );
+        assert_eq!(
+            rustdoc_with("@copydoc MEMOP_MAP".into(), &options).unwrap(),
+            "See [`MEMOP_MAP`](MemOp_MEMOP_MAP)."
+        );
+    }
+
+    #[test]
+    fn template_params() {
+        test_rustdoc!(
+            "@brief Creates a vector.\n@tparam T The type of the elements.\n@tparam A The allocator.\n@param size The size.",
+            "Creates a vector.\n# Generic Parameters\n\n* `T` - The type of the elements.\n* `A` - The allocator.\n# Arguments\n\n* `size` - The size."
+        );
+    }
+
+    #[test]
+    fn admonitions() {
+        test_rustdoc!(
+            "Maps memory.\n@warning Never map\nthe same page @b twice.\n\nNot part of it.\n@bug Crashes.\n@param addr The address",
+            "Maps memory.\n> **Warning:** Never map\n> the same page **twice.**\n\nNot part of it.\n> **Bug:** Crashes.\n# Arguments\n\n* `addr` - The address"
+        );
+        test_rustdoc!(
+            "@todo Handle\n  @b errors\n@invariant Aligned.",
+            "> **To do:** Handle\n>   **errors**\n\n> **Invariant:** Aligned."
+        );
+
+        let options = TransformOptions::new()
+            .admonition_label(Admonition::Warning, "Caution")
+            .note_style(NoteStyle::Plain);
+        assert_eq!(
+            rustdoc_with("@warning Careful\nwith this.".into(), &options).unwrap(),
+            "**Caution:** Careful\nwith this."
+        );
+    }
+
+    #[test]
+    fn safety() {
+        let input = "Maps memory.\n@pre @p addr is aligned\nand mapped.\n@warning Not thread safe.\n\nDetails.\n@post The page is mapped.\n@param addr The address";
+
+        let options = TransformOptions::new().safety_style(SafetyStyle::Safety);
+        assert_eq!(
+            rustdoc_with(input.into(), &options).unwrap(),
+            "Maps memory.\n\nDetails.\n# Arguments\n\n* `addr` - The address\n# Safety\n\n* `addr` is aligned\n  and mapped.\n* Not thread safe.\n* The page is mapped."
+        );
+
+        let options = TransformOptions::new().safety_style(SafetyStyle::Separate);
+        assert_eq!(
+            rustdoc_with(input.into(), &options).unwrap(),
+            "Maps memory.\n\nDetails.\n# Arguments\n\n* `addr` - The address\n# Preconditions\n\n* `addr` is aligned\n  and mapped.\n# Safety\n\n* Not thread safe.\n# Postconditions\n\n* The page is mapped."
+        );
+    }
+
+    #[test]
+    fn error_retvals() {
+        let input = "@retval 0 Success\n@retval 1 Pending\n@retval -1 Invalid\n@retval ERR_NO_MEMORY Out of memory";
+
+        let options = TransformOptions::new().error_retvals(["ERR_*"]);
+        assert_eq!(
+            rustdoc_with(input.into(), &options).unwrap(),
+            "# Returns\n\n* `0` - Success\n* `1` - Pending\n* `-1` - Invalid\n# Errors\n\n* `ERR_NO_MEMORY` - Out of memory"
+        );
+
+        let options = options.error_retval_predicate(|v| v.starts_with('-'));
+        assert_eq!(
+            rustdoc_with(input.into(), &options).unwrap(),
+            "# Returns\n\n* `0` - Success\n* `1` - Pending\n# Errors\n\n* `-1` - Invalid\n* `ERR_NO_MEMORY` - Out of memory"
+        );
+    }
+
+    #[test]
+    fn canonical_order() {
+        let options = TransformOptions::new()
+            .section_order(SectionOrder::Canonical)
+            .safety_style(SafetyStyle::Safety)
+            .error_retvals(["-*"]);
+
+        assert_eq!(
+            rustdoc_with(
+                "@see svcUnmapMemory\n@note Slow.\n@retval -1 Failure\n@param addr The address\nto map.\n@brief Maps memory.\n\nThe pages must be free.\n@retval 0 Success\n@throws std::bad_alloc Never\n@pre Aligned.\n@param size The size\n@remark Fast on new models.".into(),
+                &options
+            )
+            .unwrap(),
+            "Maps memory.\n\nThe pages must be free.\n# Arguments\n\n* `addr` - The address\n  to map.\n* `size` - The size\n# Returns\n\n* `0` - Success\n# Errors\n\n* `-1` - Failure\n# Safety\n\n* Aligned.\n# Throws\n\n* [`std::bad_alloc`] - Never\n# Notes\n\n* Slow.\n* Fast on new models.\n# See also\n\n* [`svcUnmapMemory`]"
+        );
+    }
+
+    #[test]
+    fn command_arguments() {
+        test_rustdoc!(
+            "@anchor map Maps memory.\n@image html map.png \"The map\"\n@image latex map.eps",
+            "Maps memory.\n![The map](map.png)\n"
+        );
+        test_rustdoc!("Maps @anchor map memory.", "Maps memory.");
+        test_rustdoc!(
+            "@xrefitem todo \"To do\" \"To do List\" Finish it",
+            "> **To do:** Finish it"
         );
     }
 
@@ -301,6 +1289,62 @@ mod test {
         );
     }
 
+    #[test]
+    fn multi_line_notes() {
+        test_rustdoc!(
+            "@note A note\nin two lines.\n\nAfter.",
+            "> **Note:** A note\n> in two lines.\n\nAfter."
+        );
+        test_rustdoc!(
+            "@since 1.0\nand later\n@deprecated Use\n  @ref other instead.\n@param x The x",
+            "> Available since: 1.0\n> and later\n\n> **Deprecated** Use\n>   [`other`] instead.\n# Arguments\n\n* `x` - The x"
+        );
+        test_rustdoc!(
+            "Maps memory. @note Not\nthread-safe.\n@note Really.",
+            "Maps memory.\n> **Note:** Not\n> thread-safe.\n> Really."
+        );
+        test_rustdoc!("@note A\n\n@note B", "> **Note:** A\n\n> **Note:** B");
+    }
+
+    #[test]
+    fn autobrief() {
+        let options = TransformOptions::new().autobrief(true);
+        let rustdoc = |input: &str| generate(normalize(input), &options).unwrap();
+
+        assert_eq!(
+            rustdoc("/**\n * Maps memory. The pages\n * must be free.\n *\n * @param a The address.\n */"),
+            "Maps memory.\n\nThe pages\nmust be free.\n\n# Arguments\n\n* `a` - The address."
+        );
+        assert_eq!(
+            rustdoc("Maps @b memory.\nDetails."),
+            "Maps **memory.**\n\nDetails."
+        );
+        assert_eq!(
+            rustdoc("Maps memory.\n@param a The address."),
+            "Maps memory.\n# Arguments\n\n* `a` - The address."
+        );
+        assert_eq!(
+            rustdoc("No period\nat all\n\nDetails. More."),
+            "No period\nat all\n\nDetails. More."
+        );
+        assert_eq!(
+            rustdoc("@brief Brief. Not split.\n\nDetails. More."),
+            "Brief. Not split.\n\nDetails. More."
+        );
+        assert_eq!(
+            rustdoc("Maps pages, e.g. this one. Details."),
+            "Maps pages, e.g. this one.\n\nDetails."
+        );
+        assert_eq!(
+            rustdoc("Needs v1.2 is enabled. Details."),
+            "Needs v1.2 is enabled.\n\nDetails."
+        );
+        assert_eq!(
+            rustdoc("Maps pages, i.e. Those of the heap. Details."),
+            "Maps pages, i.e. Those of the heap.\n\nDetails."
+        );
+    }
+
     #[test]
     fn remark() {
         test_rustdoc!(
@@ -359,6 +1403,144 @@ mod test {
         );
     }
 
+    #[test]
+    fn missing_argument() {
+        assert_eq!(
+            rustdoc("This is @b".into()),
+            Err(ParseError::MissingArgument {
+                command: "b".into(),
+                span: Span {
+                    start: 8,
+                    end: 10,
+                    line: 1,
+                    column: 9,
+                },
+            })
+        );
+
+        assert_eq!(
+            rustdoc("@retval".into()),
+            Err(ParseError::MissingArgument {
+                command: "retval".into(),
+                span: Span {
+                    start: 0,
+                    end: 7,
+                    line: 1,
+                    column: 1,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_emoji() {
+        assert_eq!(
+            rustdoc("@emoji :not_an_emoji:".into()),
+            Err(ParseError::UnknownEmoji {
+                emoji: "not_an_emoji".into(),
+                span: Span {
+                    start: 0,
+                    end: 21,
+                    line: 1,
+                    column: 1,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn code() {
+        test_rustdoc!(
+            "Example:\n@code{.c}\nint *a = &b; // @b is not bold\n@endcode\nDone.",
+            "Example:\n```c\nint *a = &b; // @b is not bold\n```\nDone."
+        );
+
+        test_rustdoc!(
+            "Example: \\code\nlet a = b;\n\\endcode",
+            "Example: \n```c\nlet a = b;\n```\n"
+        );
+    }
+
+    #[test]
+    fn code_block_language() {
+        let options = TransformOptions::new().code_block_language("rust,no_run");
+        let result = rustdoc_with("@code\nlet a = b;\n@endcode".into(), &options).unwrap();
+        assert_eq!(result, "```rust,no_run\nlet a = b;\n```\n");
+
+        let result = rustdoc_with("@code{.py}\na = b\n@endcode".into(), &options).unwrap();
+        assert_eq!(result, "```py\na = b\n```\n");
+    }
+
+    #[test]
+    fn indented_code() {
+        test_rustdoc!(
+            "Example:\n\n\tint a;\n\t\tint b;\n\nDone.",
+            "Example:\n\n```text\nint a;\n    int b;\n```\n\nDone."
+        );
+
+        test_rustdoc!("Not code:\n\tint a;", "Not code:\n    int a;");
+    }
+
+    #[test]
+    fn preformatted() {
+        test_rustdoc!(
+            "Flags:\n\n    | flag  | value |\n    |-------|-------|\n\nSee  the   table.",
+            "Flags:\n\n```text\n| flag  | value |\n|-------|-------|\n```\n\nSee the table."
+        );
+        test_rustdoc!(
+            "/*!\n    Brief\n\n    Details\n      indented\n */",
+            "Brief\n\nDetails\n  indented"
+        );
+    }
+
+    #[test]
+    fn verbatim() {
+        test_rustdoc!(
+            "@verbatim\n  \\param[in] x\n@endverbatim",
+            "```text\n  \\param[in] x\n```\n"
+        );
+    }
+
+    #[test]
+    fn options() {
+        let options = TransformOptions::new()
+            .heading_level(3)
+            .section_title(Section::Returns, "Return values")
+            .param_style(ParamStyle::Brackets)
+            .note_style(NoteStyle::Plain)
+            .link_style(LinkStyle::Code)
+            .emojis(false);
+
+        let result = rustdoc_with(
+            "@param[in,out] a The value @emoji :smile:\n@retval 0 Success\n@note Really.\n@sa other".into(),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "### Arguments\n\n* `a` \\[in, out\\] - The value :smile:\n### Return values\n\n* `0` - Success\n**Note:** Really.\n### See also\n\n* `other`"
+        );
+
+        let options = TransformOptions::new().param_style(ParamStyle::Hidden);
+        let result = rustdoc_with("@param[in] a The value".into(), &options).unwrap();
+        assert_eq!(result, "# Arguments\n\n* `a` - The value");
+    }
+
+    #[test]
+    fn lenient() {
+        let options = TransformOptions::new().strict(false);
+        let result = rustdoc_with("This is @emoji :not_an_emoji: @b".into(), &options).unwrap();
+        assert_eq!(result, "This is :not_an_emoji: ");
+    }
+
+    #[test]
+    fn comment_decorations() {
+        test_rustdoc!(
+            "/**\n * @brief Brief.\n *\n * @param a The value.\n */",
+            "Brief.\n\n# Arguments\n\n* `a` - The value."
+        );
+    }
+
     #[test]
     fn can_parse_example() {
         let example = include_str!("../tests/assets/example-bindgen.rs");
@@ -1,57 +1,158 @@
+/// A region of the original Doxygen comment.
+///
+/// `start` and `end` are byte offsets into the comment, while `line` and `column` point to where
+/// the region starts (both starting at 1, with `column` counted in characters).
+///
+/// Spans point into the comment after its C comment decorations (like `/**` or a leading `*`) are
+/// removed, which never changes the line numbers.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    /// Creates a span starting at `self` and ending where `other` ends.
+    pub(crate) fn to(self, other: Span) -> Span {
+        Span {
+            end: other.end.max(self.end),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Spanned<T> {
+    pub(crate) item: T,
+    pub(crate) span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub(crate) fn new(item: T, span: Span) -> Self {
+        Self { item, span }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum LexItem {
     At(String),
     Paren(char),
     Word(String),
     Space,
+    /// The whitespace at the start of a line, as its width in columns (with tabs stopping every
+    /// four columns).
+    Indent(usize),
     NewLine,
+    /// A block whose content isn't parsed, like `@code ... @endcode`.
+    Verbatim {
+        command: String,
+        language: Option<String>,
+        content: String,
+    },
 }
 
-pub(crate) fn lex(input: String) -> Vec<LexItem> {
-    let mut result = vec![];
+/// The commands whose content is kept verbatim, along with the command that ends them.
+const VERBATIM_COMMANDS: [(&str, &str); 2] = [("code", "endcode"), ("verbatim", "endverbatim")];
+
+pub(crate) fn lex(input: String) -> Vec<Spanned<LexItem>> {
+    let mut result: Vec<Spanned<LexItem>> = vec![];
+    let mut line = 1;
+    let mut column = 1;
+    let mut skip_until = 0;
+    let mut line_start = true;
+
+    for (start, c) in input.char_indices() {
+        let span = Span {
+            start,
+            end: start + c.len_utf8(),
+            line,
+            column,
+        };
+
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+
+        if start < skip_until {
+            if let Some(last) = result.last_mut() {
+                last.span.end = span.end;
+            }
+            continue;
+        }
+
+        if line_start && (c == ' ' || c == '\t') {
+            let width = |v: usize| if c == '\t' { v / 4 * 4 + 4 } else { v + 1 };
+            match result.last_mut() {
+                Some(Spanned {
+                    item: LexItem::Indent(v),
+                    span: last,
+                }) if last.line == span.line => {
+                    *v = width(*v);
+                    last.end = span.end;
+                }
+                _ => result.push(Spanned::new(LexItem::Indent(width(0)), span)),
+            }
+            continue;
+        }
+        line_start = c == '\n';
+
+        if c == '@' || c == '\\' {
+            if let Some((item, end)) = lex_verbatim(&input, start) {
+                skip_until = end;
+                result.push(Spanned::new(item, span));
+                continue;
+            }
+        }
 
-    for c in input.chars() {
         match c {
             '@' => {
-                result.push(LexItem::At(c.into()));
+                result.push(Spanned::new(LexItem::At(c.into()), span));
             }
             '\\' => {
-                if let Some(value) = result.last_mut() {
-                    match value {
-                        LexItem::At(v) => {
-                            if v == "\\" {
-                                *v += "\\"
-                            } else {
-                                result.push(LexItem::At(c.into()))
-                            }
-                        }
-                        _ => result.push(LexItem::At(c.into())),
+                if let Some(Spanned {
+                    item: LexItem::At(v),
+                    span: last,
+                }) = result.last_mut()
+                {
+                    if v == "\\" {
+                        *v += "\\";
+                        last.end = span.end;
+                        continue;
                     }
-                } else {
-                    result.push(LexItem::At(c.into()));
                 }
+
+                result.push(Spanned::new(LexItem::At(c.into()), span));
             }
             '{' | '}' => {
-                result.push(LexItem::Paren(c));
+                result.push(Spanned::new(LexItem::Paren(c), span));
             }
             ' ' => {
-                if let Some(v) = result.last_mut() {
-                    if !matches!(v, LexItem::Space) {
-                        result.push(LexItem::Space);
+                if let Some(last) = result.last_mut() {
+                    if matches!(last.item, LexItem::Space) {
+                        last.span.end = span.end;
+                    } else {
+                        result.push(Spanned::new(LexItem::Space, span));
                     }
                 }
             }
             '\n' => {
-                result.push(LexItem::NewLine);
+                result.push(Spanned::new(LexItem::NewLine, span));
             }
             _ => {
-                if let Some(v) = result.last_mut() {
-                    match v {
-                        LexItem::Word(v) => *v += &c.to_string(),
-                        _ => result.push(LexItem::Word(String::from(c))),
-                    }
+                if let Some(Spanned {
+                    item: LexItem::Word(v),
+                    span: last,
+                }) = result.last_mut()
+                {
+                    v.push(c);
+                    last.end = span.end;
                 } else {
-                    result.push(LexItem::Word(String::from(c)))
+                    result.push(Spanned::new(LexItem::Word(String::from(c)), span));
                 }
             }
         }
@@ -60,13 +161,93 @@ pub(crate) fn lex(input: String) -> Vec<LexItem> {
     result
 }
 
+/// Lexes a verbatim block if the command starting at `start` opens one, returning it along with
+/// the byte offset where the block ends.
+///
+/// The command is only recognized at the start of a word, so `user@code.com` isn't a code block.
+fn lex_verbatim(input: &str, start: usize) -> Option<(LexItem, usize)> {
+    if input[..start].ends_with(|c: char| !c.is_whitespace()) {
+        return None;
+    }
+
+    let rest = &input[start + 1..];
+    let (command, end_command) = VERBATIM_COMMANDS.iter().find(|(command, _)| {
+        rest.starts_with(command)
+            && !rest[command.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+    })?;
+
+    let mut rest = &rest[command.len()..];
+    let mut language = None;
+    if *command == "code" && rest.starts_with('{') {
+        if let Some(end) = rest.find('}') {
+            language = Some(rest[1..end].trim().trim_start_matches('.').to_string())
+                .filter(|v| !v.is_empty());
+            rest = &rest[end + 1..];
+        }
+    }
+
+    let content_start = input.len() - rest.len();
+    let (content, end) = ['@', '\\']
+        .iter()
+        .filter_map(|marker| {
+            rest.match_indices(&format!("{marker}{end_command}"))
+                .map(|(index, _)| index)
+                .find(|index| {
+                    !rest[index + end_command.len() + 1..]
+                        .starts_with(|c: char| c.is_alphanumeric() || c == '_')
+                })
+        })
+        .min()
+        .map(|index| {
+            (
+                &rest[..index],
+                content_start + index + end_command.len() + 1,
+            )
+        })
+        .unwrap_or((rest, input.len()));
+
+    Some((
+        LexItem::Verbatim {
+            command: command.to_string(),
+            language,
+            content: trim_verbatim(content),
+        },
+        end,
+    ))
+}
+
+/// Removes the rest of the line that opens a verbatim block and the indentation of the line that
+/// closes it, as long as they are only whitespace.
+fn trim_verbatim(content: &str) -> String {
+    let Some(first_newline) = content.find('\n') else {
+        return content.trim().into();
+    };
+
+    let mut content = content;
+    if content[..first_newline].trim().is_empty() {
+        content = &content[first_newline + 1..];
+    }
+
+    if let Some(last_newline) = content.rfind('\n') {
+        if content[last_newline..].trim().is_empty() {
+            content = &content[..last_newline];
+        }
+    }
+
+    content.into()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    fn lex_items(input: &str) -> Vec<LexItem> {
+        lex(input.into()).into_iter().map(|v| v.item).collect()
+    }
+
     #[test]
     fn basic_notation() {
-        let result = lex("@name Memory Management".into());
+        let result = lex_items("@name Memory Management");
         assert_eq!(
             result,
             vec![
@@ -79,7 +260,7 @@ mod test {
             ]
         );
 
-        let result = lex("\\name Memory Management".into());
+        let result = lex_items("\\name Memory Management");
         assert_eq!(
             result,
             vec![
@@ -92,7 +273,7 @@ mod test {
             ]
         );
 
-        let result = lex("\\\\name Memory Management".into());
+        let result = lex_items("\\\\name Memory Management");
         assert_eq!(
             result,
             vec![
@@ -108,7 +289,7 @@ mod test {
 
     #[test]
     fn basic_groups() {
-        let result = lex("@{\n* @name Memory Management\n@}".into());
+        let result = lex_items("@{\n* @name Memory Management\n@}");
         assert_eq!(
             result,
             vec![
@@ -129,4 +310,146 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn indentation() {
+        let result = lex_items("  a  b\n\t c\n \nd");
+        assert_eq!(
+            result,
+            vec![
+                LexItem::Indent(2),
+                LexItem::Word("a".into()),
+                LexItem::Space,
+                LexItem::Word("b".into()),
+                LexItem::NewLine,
+                LexItem::Indent(5),
+                LexItem::Word("c".into()),
+                LexItem::NewLine,
+                LexItem::Indent(1),
+                LexItem::NewLine,
+                LexItem::Word("d".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn spans() {
+        let result = lex("@brief  Hi\n\\\\c été".into());
+        let spans = result.iter().map(|v| v.span).collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                Span {
+                    start: 0,
+                    end: 1,
+                    line: 1,
+                    column: 1
+                },
+                Span {
+                    start: 1,
+                    end: 6,
+                    line: 1,
+                    column: 2
+                },
+                Span {
+                    start: 6,
+                    end: 8,
+                    line: 1,
+                    column: 7
+                },
+                Span {
+                    start: 8,
+                    end: 10,
+                    line: 1,
+                    column: 9
+                },
+                Span {
+                    start: 10,
+                    end: 11,
+                    line: 1,
+                    column: 11
+                },
+                Span {
+                    start: 11,
+                    end: 13,
+                    line: 2,
+                    column: 1
+                },
+                Span {
+                    start: 13,
+                    end: 14,
+                    line: 2,
+                    column: 3
+                },
+                Span {
+                    start: 14,
+                    end: 15,
+                    line: 2,
+                    column: 4
+                },
+                Span {
+                    start: 15,
+                    end: 20,
+                    line: 2,
+                    column: 5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn verbatim() {
+        let result = lex_items("Example:\n@code{.c}\nint a = b @ c;\n  \\n\n@endcode\nDone");
+        assert_eq!(
+            result,
+            vec![
+                LexItem::Word("Example:".into()),
+                LexItem::NewLine,
+                LexItem::Verbatim {
+                    command: "code".into(),
+                    language: Some("c".into()),
+                    content: "int a = b @ c;\n  \\n".into(),
+                },
+                LexItem::NewLine,
+                LexItem::Word("Done".into()),
+            ]
+        );
+
+        let result = lex_items("\\verbatim @b not bold \\endverbatim");
+        assert_eq!(
+            result,
+            vec![LexItem::Verbatim {
+                command: "verbatim".into(),
+                language: None,
+                content: "@b not bold".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn verbatim_inside_word() {
+        let result = lex_items("Contact me at user@code.com for help");
+        assert!(!result.iter().any(|v| matches!(v, LexItem::Verbatim { .. })));
+    }
+
+    #[test]
+    fn unterminated_verbatim() {
+        let result = lex("@code\nint a;".into());
+        assert_eq!(
+            result,
+            vec![Spanned::new(
+                LexItem::Verbatim {
+                    command: "code".into(),
+                    language: None,
+                    content: "int a;".into(),
+                },
+                Span {
+                    start: 0,
+                    end: 12,
+                    line: 1,
+                    column: 1
+                }
+            )]
+        );
+    }
 }
@@ -27,17 +27,104 @@
 //!     }
 //! }
 //! ```
+//!
+//! With the `bindgen` feature enabled, `doxygen_rs::bindgen::DoxygenCallbacks` implements these
+//! callbacks, and can be configured with [`TransformOptions`].
 
+pub mod ast;
+#[cfg(feature = "bindgen")]
+pub mod bindgen;
+mod commands;
+pub mod doc;
 mod emojis;
 pub mod generator;
 mod lexer;
+mod normalize;
+mod options;
 mod parser;
+mod resolver;
+pub mod tagfile;
+mod transformer;
+
+pub use lexer::Span;
+pub use options::{
+    Admonition, GroupStyle, LinkStyle, NoteStyle, ParamStyle, SafetyStyle, Section, SectionOrder,
+    TransformOptions,
+};
+pub use parser::ParseError;
+pub use resolver::SymbolResolver;
+pub use transformer::Transformer;
+
+/// This function parses the Doxygen of a single element (function, struct, etc.) into an
+/// [`ast::Comment`], which can be used to write custom generators, lints or extractors.
+///
+/// # Examples
+///
+/// ```
+/// use doxygen_rs::ast::Block;
+///
+/// let comment = doxygen_rs::parse("@brief Example Doxygen brief\n@return Nothing").unwrap();
+/// assert_eq!(comment.blocks.len(), 2);
+/// assert!(matches!(&comment.blocks[1], Block::Command { command, .. } if command.name == "return"));
+/// ```
+///
+/// # Errors
+///
+/// This function will error if the comment can't be parsed, see [`ParseError`].
+pub fn parse(value: &str) -> Result<ast::Comment, ParseError> {
+    ast::parse(&normalize::normalize(value))
+}
 
 /// This function transforms the Doxygen of a single element (function, struct, etc.)
 ///
 /// # Panics
 ///
-/// This function will panic if any error from [`generator::rustdoc`] is returned.
+/// This function will panic if any error from [`generator::rustdoc`] is returned. Use
+/// [`try_transform`] to handle those errors instead.
 pub fn transform(value: &str) -> String {
-    generator::rustdoc(value.into()).expect("failed to transform the comments")
+    try_transform(value).expect("failed to transform the comments")
+}
+
+/// This function transforms the Doxygen of a single element (function, struct, etc.), returning
+/// an error instead of panicking when the comment is malformed.
+///
+/// # Examples
+///
+/// ```
+/// use doxygen_rs::{try_transform, ParseError};
+///
+/// assert_eq!(try_transform("@b bold").unwrap(), "**bold**");
+/// assert!(matches!(
+///     try_transform("@emoji :not_an_emoji:"),
+///     Err(ParseError::UnknownEmoji { .. })
+/// ));
+/// ```
+///
+/// # Errors
+///
+/// See [`generator::rustdoc`].
+pub fn try_transform(value: &str) -> Result<String, ParseError> {
+    generator::rustdoc(value.into())
+}
+
+/// This function transforms the Doxygen of a single element (function, struct, etc.) using the
+/// given [`TransformOptions`].
+///
+/// # Panics
+///
+/// This function will panic if any error from [`generator::rustdoc_with`] is returned. Use
+/// [`try_transform_with`] to handle those errors instead.
+pub fn transform_with(value: &str, options: &TransformOptions) -> String {
+    try_transform_with(value, options).expect("failed to transform the comments")
+}
+
+/// This function transforms the Doxygen of a single element (function, struct, etc.) using the
+/// given [`TransformOptions`], returning an error instead of panicking when the comment is
+/// malformed.
+///
+/// # Errors
+///
+/// See [`generator::rustdoc_with`].
+pub fn try_transform_with(value: &str, options: &TransformOptions) -> Result<String, ParseError> {
+    generator::rustdoc_with(value.into(), options)
 }
@@ -0,0 +1,188 @@
+/// Removes the decorations of C and C++ comments, so only the Doxygen content is left.
+///
+/// Recognizes the Javadoc (`/** ... */`, with or without a leading `*` column), Qt
+/// (`/*! ... */`) and C++ (`///`, `//!`) styles, including the `<` used to document members
+/// after their declaration. The decoration column is stripped the same way on every line, keeping
+/// the relative indentation of the content.
+///
+/// Lines are never removed (except for blank lines at the end), so the line numbers of the
+/// [`Span`](crate::Span)s still match the original comment.
+pub(crate) fn normalize(input: &str) -> String {
+    let input = input.replace("\r\n", "\n");
+    let mut lines = input
+        .split('\n')
+        .map(|v| (v.to_string(), false))
+        .collect::<Vec<_>>();
+
+    let mut opened = false;
+    if let Some((first, _)) = lines.first_mut() {
+        let stripped = strip_opening(first);
+        opened = stripped != *first;
+        *first = stripped;
+    }
+
+    let mut closed = false;
+    if let Some((last, _)) = lines.last_mut() {
+        let stripped = strip_closing(last);
+        closed = stripped != *last;
+        *last = stripped;
+    }
+
+    // A leading `*` is only a decoration in block comments (bindgen drops the `/` of `/**`, but
+    // keeps the `*/`), or when every line has one; otherwise it's a Markdown bullet
+    let stars = opened
+        || closed
+        || lines
+            .iter()
+            .filter(|(line, _)| !line.trim().is_empty())
+            .all(|(line, _)| strip_star(line).is_some());
+
+    for (line, decorated) in &mut lines {
+        let stripped = strip_line_comment(line).or_else(|| strip_star(line).filter(|_| stars));
+        if let Some(stripped) = stripped {
+            *line = stripped;
+            *decorated = true;
+        }
+    }
+
+    // Decorations are usually followed by a space, which isn't part of the indentation
+    // The lines of a comment without decorations (like `/*! ... */`) are indented like the code
+    // around it, except for the line that opens it
+    let indentation = |decorated: bool| {
+        lines
+            .iter()
+            .skip(usize::from(!decorated))
+            .filter(|(line, v)| *v == decorated && !line.trim().is_empty())
+            .map(|(line, _)| line.len() - line.trim_start_matches([' ', '\t']).len())
+            .min()
+            .unwrap_or_default()
+    };
+    let decorated_indentation = indentation(true);
+    let undecorated_indentation = if opened { indentation(false) } else { 0 };
+
+    let mut result = lines
+        .into_iter()
+        .enumerate()
+        .map(|(index, (line, decorated))| {
+            if line.trim().is_empty() && (decorated || index > 0) {
+                String::new()
+            } else if decorated {
+                line[decorated_indentation..].to_string()
+            } else if index > 0 {
+                line[undecorated_indentation..].to_string()
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>();
+
+    while result.len() > 1 && result.last().is_some_and(|v| v.trim().is_empty()) {
+        result.pop();
+    }
+
+    result.join("\n")
+}
+
+fn strip_opening(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let Some(rest) = ["/**<", "/*!<", "/**", "/*!", "/*"]
+        .iter()
+        .find_map(|v| trimmed.strip_prefix(v))
+    else {
+        return line.into();
+    };
+
+    // `/*****` banners
+    if rest.trim_start_matches('*').trim().is_empty() {
+        return String::new();
+    }
+
+    rest.strip_prefix(' ').unwrap_or(rest).into()
+}
+
+fn strip_closing(line: &str) -> String {
+    let Some(rest) = line.trim_end().strip_suffix("*/") else {
+        return line.into();
+    };
+
+    rest.trim_end_matches('*').trim_end().into()
+}
+
+fn strip_line_comment(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    ["///<", "//!<", "///", "//!"]
+        .iter()
+        .find_map(|v| trimmed.strip_prefix(v))
+        .map(String::from)
+}
+
+/// Strips the `*` column of Javadoc comments, without touching Markdown emphasis like `**Note**`.
+fn strip_star(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    let rest = trimmed.trim_start_matches('*');
+
+    if rest.len() == trimmed.len() || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+
+    Some(rest.into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn javadoc() {
+        assert_eq!(
+            normalize(
+                "/**\n * @brief Brief\n *\n * @param a    Argument\n *              continued\n */"
+            ),
+            "\n@brief Brief\n\n@param a    Argument\n             continued"
+        );
+        assert_eq!(normalize("/** @brief Brief */"), "@brief Brief");
+        assert_eq!(normalize("/**< Member */"), "Member");
+    }
+
+    #[test]
+    fn qt() {
+        assert_eq!(
+            normalize("/*!\n    \\brief Brief\n\n    Details\n      Indented\n*/"),
+            "\n\\brief Brief\n\nDetails\n  Indented"
+        );
+    }
+
+    #[test]
+    fn triple_slash() {
+        assert_eq!(
+            normalize("/// @brief Brief\n///   Indented\n///< Member\n//! Qt"),
+            "@brief Brief\n  Indented\nMember\nQt"
+        );
+    }
+
+    #[test]
+    fn bindgen() {
+        assert_eq!(
+            normalize("@name Group\n@{\n**\n* @brief Brief\n*\n* Details\n*/"),
+            "@name Group\n@{\n\n@brief Brief\n\nDetails"
+        );
+    }
+
+    #[test]
+    fn keeps_emphasis() {
+        assert_eq!(
+            normalize("**Note:** This is *important*\n- a\n* b"),
+            "**Note:** This is *important*\n- a\n* b"
+        );
+    }
+
+    #[test]
+    fn keeps_bullets() {
+        assert_eq!(normalize("Items:\n* one\n* two"), "Items:\n* one\n* two");
+        assert_eq!(
+            normalize("/**\n * Items:\n * * one\n */"),
+            "\nItems:\n* one"
+        );
+        assert_eq!(normalize(" * Brief\n *\n * Details"), "Brief\n\nDetails");
+    }
+}
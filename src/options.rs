@@ -0,0 +1,604 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+
+use crate::SymbolResolver;
+
+type ErrorPredicate = dyn Fn(&str) -> bool + Send + Sync + RefUnwindSafe;
+
+/// Options to customize the Rustdoc created by [`generator::rustdoc_with`](crate::generator::rustdoc_with)
+/// and [`transform_with`](crate::transform_with).
+///
+/// # Examples
+///
+/// ```
+/// use doxygen_rs::{transform_with, NoteStyle, Section, TransformOptions};
+///
+/// let options = TransformOptions::new()
+///     .heading_level(2)
+///     .section_title(Section::Arguments, "Parameters")
+///     .note_style(NoteStyle::Plain);
+///
+/// let rustdoc = transform_with("@param a The value\n@note Be careful", &options);
+/// assert_eq!(rustdoc, "## Parameters\n\n* `a` - The value\n**Note:** Be careful");
+/// ```
+#[derive(Clone)]
+pub struct TransformOptions {
+    pub(crate) code_block_language: String,
+    pub(crate) heading_level: usize,
+    pub(crate) section_titles: HashMap<Section, String>,
+    pub(crate) admonition_labels: HashMap<Admonition, String>,
+    pub(crate) param_style: ParamStyle,
+    pub(crate) note_style: NoteStyle,
+    pub(crate) link_style: LinkStyle,
+    pub(crate) emojis: bool,
+    pub(crate) strict: bool,
+    pub(crate) group_style: GroupStyle,
+    pub(crate) safety_style: SafetyStyle,
+    pub(crate) section_order: SectionOrder,
+    pub(crate) autobrief: bool,
+    pub(crate) error_patterns: Vec<String>,
+    pub(crate) error_predicate: Option<Arc<ErrorPredicate>>,
+    pub(crate) symbol_resolver: Option<Arc<dyn SymbolResolver>>,
+}
+
+impl Default for TransformOptions {
+    fn default() -> Self {
+        Self {
+            code_block_language: "c".into(),
+            heading_level: 1,
+            section_titles: HashMap::new(),
+            admonition_labels: HashMap::new(),
+            param_style: ParamStyle::Direction,
+            note_style: NoteStyle::Quote,
+            link_style: LinkStyle::IntraDoc,
+            emojis: true,
+            strict: true,
+            group_style: GroupStyle::Ignore,
+            safety_style: SafetyStyle::Inline,
+            section_order: SectionOrder::Source,
+            autobrief: false,
+            error_patterns: vec![],
+            error_predicate: None,
+            symbol_resolver: None,
+        }
+    }
+}
+
+impl Debug for TransformOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransformOptions")
+            .field("code_block_language", &self.code_block_language)
+            .field("heading_level", &self.heading_level)
+            .field("section_titles", &self.section_titles)
+            .field("admonition_labels", &self.admonition_labels)
+            .field("param_style", &self.param_style)
+            .field("note_style", &self.note_style)
+            .field("link_style", &self.link_style)
+            .field("emojis", &self.emojis)
+            .field("strict", &self.strict)
+            .field("group_style", &self.group_style)
+            .field("safety_style", &self.safety_style)
+            .field("section_order", &self.section_order)
+            .field("autobrief", &self.autobrief)
+            .field("error_patterns", &self.error_patterns)
+            .field(
+                "error_predicate",
+                &self.error_predicate.as_ref().map(|_| "Fn(&str) -> bool"),
+            )
+            .field("symbol_resolver", &self.symbol_resolver)
+            .finish()
+    }
+}
+
+impl PartialEq for TransformOptions {
+    /// Symbol resolvers and `@retval` predicates are only equal if they are the same instance.
+    fn eq(&self, other: &Self) -> bool {
+        let resolvers_eq = match (&self.symbol_resolver, &other.symbol_resolver) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        let predicates_eq = match (&self.error_predicate, &other.error_predicate) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+
+        self.code_block_language == other.code_block_language
+            && self.heading_level == other.heading_level
+            && self.section_titles == other.section_titles
+            && self.admonition_labels == other.admonition_labels
+            && self.param_style == other.param_style
+            && self.note_style == other.note_style
+            && self.link_style == other.link_style
+            && self.emojis == other.emojis
+            && self.strict == other.strict
+            && self.group_style == other.group_style
+            && self.safety_style == other.safety_style
+            && self.section_order == other.section_order
+            && self.autobrief == other.autobrief
+            && self.error_patterns == other.error_patterns
+            && predicates_eq
+            && resolvers_eq
+    }
+}
+
+impl Eq for TransformOptions {}
+
+/// The sections created by the generator, whose titles can be changed with
+/// [`TransformOptions::section_title`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Section {
+    /// The parameters documented with `@param`.
+    Arguments,
+    /// The return values documented with `@return` and `@retval`.
+    Returns,
+    /// The exceptions documented with `@throw`.
+    Throws,
+    /// The template parameters documented with `@tparam`.
+    GenericParameters,
+    /// The return values documented with `@retval` that are errors, see
+    /// [`TransformOptions::error_retvals`].
+    Errors,
+    /// The preconditions, postconditions and warnings collected according to
+    /// [`TransformOptions::safety_style`].
+    Safety,
+    /// The preconditions documented with `@pre`, with [`SafetyStyle::Separate`].
+    Preconditions,
+    /// The postconditions documented with `@post`, with [`SafetyStyle::Separate`].
+    Postconditions,
+    /// The notes documented with `@note` and `@remark`, with [`SectionOrder::Canonical`].
+    Notes,
+    /// The references of `@sa` and `@see`, which are always collected into a list at the end.
+    SeeAlso,
+}
+
+impl Section {
+    /// Returns the title used when no other one is given.
+    pub fn default_title(self) -> &'static str {
+        match self {
+            Section::Arguments => "Arguments",
+            Section::Returns => "Returns",
+            Section::Throws => "Throws",
+            Section::GenericParameters => "Generic Parameters",
+            Section::Errors => "Errors",
+            Section::Safety => "Safety",
+            Section::Preconditions => "Preconditions",
+            Section::Postconditions => "Postconditions",
+            Section::Notes => "Notes",
+            Section::SeeAlso => "See also",
+        }
+    }
+}
+
+/// The commands shown as a labelled block spanning their whole paragraph, whose labels can be
+/// changed with [`TransformOptions::admonition_label`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Admonition {
+    /// `@warning`
+    Warning,
+    /// `@attention`
+    Attention,
+    /// `@important`
+    Important,
+    /// `@bug`
+    Bug,
+    /// `@todo`
+    Todo,
+    /// `@test`
+    Test,
+    /// `@invariant`
+    Invariant,
+}
+
+impl Admonition {
+    /// Returns the admonition of a Doxygen command, like [`Admonition::Warning`] for `warning`.
+    pub fn from_command(command: &str) -> Option<Self> {
+        Some(match command {
+            "warning" => Admonition::Warning,
+            "attention" => Admonition::Attention,
+            "important" => Admonition::Important,
+            "bug" => Admonition::Bug,
+            "todo" => Admonition::Todo,
+            "test" => Admonition::Test,
+            "invariant" => Admonition::Invariant,
+            _ => return None,
+        })
+    }
+
+    /// Returns the label used when no other one is given.
+    pub fn default_label(self) -> &'static str {
+        match self {
+            Admonition::Warning => "Warning",
+            Admonition::Attention => "Attention",
+            Admonition::Important => "Important",
+            Admonition::Bug => "Bug",
+            Admonition::Todo => "To do",
+            Admonition::Test => "Test",
+            Admonition::Invariant => "Invariant",
+        }
+    }
+}
+
+/// Whether `@pre`, `@post` and `@warning` are collected into sections at the end of the
+/// documentation, like the `# Safety` section expected on `unsafe` functions.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum SafetyStyle {
+    /// The commands are shown where they are written.
+    Inline,
+    /// Every `@pre`, `@post` and `@warning` is an item of `# Safety`.
+    Safety,
+    /// Every `@warning` is an item of `# Safety`, every `@pre` of `# Preconditions` and every
+    /// `@post` of `# Postconditions`.
+    Separate,
+}
+
+/// The order of the sections created by the generator.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum SectionOrder {
+    /// The sections are added in the order of the comment, and a section whose commands aren't
+    /// consecutive is split.
+    Source,
+    /// The summary (from `@brief`) is followed by the details, then by the sections in the order
+    /// Arguments, Generic Parameters, Returns, Errors, Safety, Preconditions, Postconditions,
+    /// Throws, Notes and See also. Every section is added once, with all its items.
+    Canonical,
+}
+
+/// How the direction of a `@param` is shown.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum ParamStyle {
+    /// `` * `name` (direction in, out) - ``
+    Direction,
+    /// `` * `name` \[in, out\] - ``
+    Brackets,
+    /// `` * `name` - ``, without the direction.
+    Hidden,
+}
+
+/// How `@note`, `@remark`, `@since`, `@deprecated` and the [`Admonition`]s are shown.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum NoteStyle {
+    /// `> **Note:** text`
+    Quote,
+    /// `**Note:** text`
+    Plain,
+}
+
+/// How references (like the ones from `@ref`, `@sa` or `@throw`) are shown.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum LinkStyle {
+    /// ``[`name`]``, an intra-doc link.
+    IntraDoc,
+    /// `` `name` ``, without a link.
+    Code,
+    /// `name`, as plain text.
+    Plain,
+}
+
+/// How the members of a group (`@name` and `@{`/`@}`) are marked by a
+/// [`Transformer`](crate::Transformer).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum GroupStyle {
+    /// The members aren't marked.
+    Ignore,
+    /// `**Name:**` is added before the documentation of every member.
+    Prefix,
+    /// A `**Group:** Name` note is added after the documentation of every member.
+    Annotate,
+}
+
+impl TransformOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the info string of the code blocks created from `@code` when no language is given
+    /// with `@code{.lang}`. Defaults to `c`.
+    ///
+    /// Rustdoc compiles code blocks without an info string (or with `rust`) as doctests, so this
+    /// shouldn't be empty unless the examples are Rust code. Use `ignore` or `no_run` to keep
+    /// Rust examples from being run.
+    pub fn code_block_language(mut self, language: impl Into<String>) -> Self {
+        self.code_block_language = language.into();
+        self
+    }
+
+    /// Sets the level of the headings created by the generator, between 1 (`#`) and 6 (`######`).
+    /// Defaults to 1.
+    pub fn heading_level(mut self, level: usize) -> Self {
+        self.heading_level = level.clamp(1, 6);
+        self
+    }
+
+    /// Sets the title of a section, see [`Section::default_title`] for the default ones.
+    pub fn section_title(mut self, section: Section, title: impl Into<String>) -> Self {
+        self.section_titles.insert(section, title.into());
+        self
+    }
+
+    /// Sets the label of an admonition, see [`Admonition::default_label`] for the default ones.
+    pub fn admonition_label(mut self, admonition: Admonition, label: impl Into<String>) -> Self {
+        self.admonition_labels.insert(admonition, label.into());
+        self
+    }
+
+    /// Sets how the direction of a `@param` is shown. Defaults to [`ParamStyle::Direction`].
+    pub fn param_style(mut self, style: ParamStyle) -> Self {
+        self.param_style = style;
+        self
+    }
+
+    /// Sets how notes are shown. Defaults to [`NoteStyle::Quote`].
+    pub fn note_style(mut self, style: NoteStyle) -> Self {
+        self.note_style = style;
+        self
+    }
+
+    /// Sets how references are shown. Defaults to [`LinkStyle::IntraDoc`].
+    pub fn link_style(mut self, style: LinkStyle) -> Self {
+        self.link_style = style;
+        self
+    }
+
+    /// Sets whether `@emoji :name:` is replaced by the emoji. When disabled, `:name:` is kept as
+    /// text. Defaults to `true`.
+    pub fn emojis(mut self, enabled: bool) -> Self {
+        self.emojis = enabled;
+        self
+    }
+
+    /// Sets whether commands missing their argument or unknown emojis are errors. When disabled,
+    /// those commands are dropped (or, for emojis, kept as `:name:`). Defaults to `true`.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets how the members of a group are marked. Only used by a
+    /// [`Transformer`](crate::Transformer). Defaults to [`GroupStyle::Ignore`].
+    pub fn group_style(mut self, style: GroupStyle) -> Self {
+        self.group_style = style;
+        self
+    }
+
+    /// Sets whether `@pre`, `@post` and `@warning` are collected into sections. Defaults to
+    /// [`SafetyStyle::Inline`].
+    ///
+    /// Bindgen generates `unsafe` functions, which `clippy::missing_safety_doc` expects to have a
+    /// `# Safety` section.
+    pub fn safety_style(mut self, style: SafetyStyle) -> Self {
+        self.safety_style = style;
+        self
+    }
+
+    /// Sets the order of the sections. Defaults to [`SectionOrder::Source`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doxygen_rs::{transform_with, SectionOrder, TransformOptions};
+    ///
+    /// let options = TransformOptions::new().section_order(SectionOrder::Canonical);
+    ///
+    /// let rustdoc = transform_with(
+    ///     "@return The sum\n@param a First\n@brief Adds\n@param b Second",
+    ///     &options,
+    /// );
+    /// assert_eq!(
+    ///     rustdoc,
+    ///     "Adds\n# Arguments\n\n* `a` - First\n* `b` - Second\n# Returns\n\nThe sum"
+    /// );
+    /// ```
+    pub fn section_order(mut self, order: SectionOrder) -> Self {
+        self.section_order = order;
+        self
+    }
+
+    /// Sets whether the first sentence of a comment without `@brief` is its summary, like the
+    /// `JAVADOC_AUTOBRIEF` and `QT_AUTOBRIEF` options of Doxygen. Defaults to `false`.
+    ///
+    /// The sentence ends at the first period followed by whitespace and a word that doesn't start
+    /// in lowercase, skipping abbreviations like `e.g.` (or at the end of the first paragraph). It
+    /// is separated from the rest by a blank line, since rustdoc shows the whole first paragraph
+    /// as the summary of the item in module listings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doxygen_rs::{transform_with, TransformOptions};
+    ///
+    /// let options = TransformOptions::new().autobrief(true);
+    ///
+    /// let rustdoc = transform_with("Maps memory. The pages must be free.", &options);
+    /// assert_eq!(rustdoc, "Maps memory.\n\nThe pages must be free.");
+    /// ```
+    pub fn autobrief(mut self, enabled: bool) -> Self {
+        self.autobrief = enabled;
+        self
+    }
+
+    /// Sets the patterns of the `@retval`s that are errors, which are listed in `# Errors` instead
+    /// of `# Returns`. A `*` in a pattern matches any text, so `ERR_*` matches `ERR_NO_MEMORY`.
+    ///
+    /// Rustdoc documents the errors of a function in `# Errors`, which
+    /// `clippy::missing_errors_doc` expects on functions returning a `Result`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doxygen_rs::{transform_with, TransformOptions};
+    ///
+    /// let options = TransformOptions::new().error_retvals(["ERR_*", "-*"]);
+    ///
+    /// let rustdoc = transform_with("@retval 0 Success\n@retval ERR_NO_MEMORY Out of memory", &options);
+    /// assert_eq!(rustdoc, "# Returns\n\n* `0` - Success\n# Errors\n\n* `ERR_NO_MEMORY` - Out of memory");
+    /// ```
+    pub fn error_retvals(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.error_patterns = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets a function deciding whether a `@retval` is an error, in addition to the patterns of
+    /// [`TransformOptions::error_retvals`].
+    pub fn error_retval_predicate(
+        mut self,
+        predicate: impl Fn(&str) -> bool + Send + Sync + RefUnwindSafe + 'static,
+    ) -> Self {
+        self.error_predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Sets the [`SymbolResolver`] used to find the targets of the links. Without one, links
+    /// point to the names used in the comment.
+    pub fn symbol_resolver(mut self, resolver: impl SymbolResolver + 'static) -> Self {
+        self.symbol_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Returns the section `command` is collected into, if any.
+    pub(crate) fn collected_section(&self, command: &str) -> Option<Section> {
+        match (self.safety_style, command) {
+            (SafetyStyle::Inline, _) => None,
+            (_, "warning") | (SafetyStyle::Safety, "pre" | "post") => Some(Section::Safety),
+            (SafetyStyle::Separate, "pre") => Some(Section::Preconditions),
+            (SafetyStyle::Separate, "post") => Some(Section::Postconditions),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_error_retval(&self, value: &str) -> bool {
+        self.error_predicate.as_ref().is_some_and(|v| v(value))
+            || self
+                .error_patterns
+                .iter()
+                .any(|pattern| matches_pattern(pattern, value))
+    }
+
+    pub(crate) fn heading(&self, title: &str) -> String {
+        format!("{} {}", "#".repeat(self.heading_level), title)
+    }
+
+    pub(crate) fn section_heading(&self, section: Section) -> String {
+        let title = self
+            .section_titles
+            .get(&section)
+            .map(String::as_str)
+            .unwrap_or(section.default_title());
+
+        format!("{}\n\n", self.heading(title))
+    }
+
+    pub(crate) fn admonition(&self, admonition: Admonition) -> String {
+        let label = self
+            .admonition_labels
+            .get(&admonition)
+            .map(String::as_str)
+            .unwrap_or(admonition.default_label());
+
+        self.note(&format!("**{label}:** "))
+    }
+
+    pub(crate) fn note(&self, label: &str) -> String {
+        match self.note_style {
+            NoteStyle::Quote => format!("> {label}"),
+            NoteStyle::Plain => label.into(),
+        }
+    }
+
+    pub(crate) fn link(&self, name: &str) -> String {
+        match self.link_style {
+            LinkStyle::IntraDoc => match self.resolve(name) {
+                Some(target) if target != name => format!("[`{name}`]({target})"),
+                _ => format!("[`{name}`]"),
+            },
+            LinkStyle::Code => format!("`{name}`"),
+            LinkStyle::Plain => name.into(),
+        }
+    }
+
+    /// Like [`link`](Self::link), but shows `text` instead of the name.
+    pub(crate) fn link_text(&self, name: &str, text: &str) -> String {
+        match self.link_style {
+            LinkStyle::IntraDoc => {
+                let target = self.resolve(name);
+                format!("[{text}]({})", target.as_deref().unwrap_or(name))
+            }
+            LinkStyle::Code | LinkStyle::Plain => text.into(),
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.symbol_resolver.as_ref()?.resolve(name)
+    }
+}
+
+/// Matches `value` against a pattern where `*` matches any text.
+fn matches_pattern(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*` in the pattern
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::panic::UnwindSafe;
+
+    #[test]
+    fn patterns() {
+        assert!(matches_pattern("ERR_*", "ERR_NO_MEMORY"));
+        assert!(matches_pattern("*_FAILED", "MAP_FAILED"));
+        assert!(matches_pattern("E*_*", "EINVAL_X"));
+        assert!(matches_pattern("-1", "-1"));
+        assert!(!matches_pattern("-1", "-10"));
+        assert!(!matches_pattern("ERR_*", "OK"));
+        assert!(!matches_pattern("A*B*B", "AB"));
+    }
+
+    #[test]
+    fn auto_traits() {
+        fn assert_auto_traits<T: Send + Sync + UnwindSafe + RefUnwindSafe>() {}
+        assert_auto_traits::<TransformOptions>();
+    }
+
+    #[test]
+    fn error_predicate() {
+        let code = String::from("EAGAIN");
+        let options = TransformOptions::new().error_retval_predicate(move |v| v == code);
+        assert!(options.is_error_retval("EAGAIN"));
+        assert!(!options.is_error_retval("OK"));
+
+        assert_eq!(options, options.clone());
+        assert_ne!(options, options.clone().error_retval_predicate(|_| true));
+        assert_ne!(options, TransformOptions::new());
+    }
+}
@@ -1,17 +1,77 @@
-use crate::lexer::{lex, LexItem};
+use crate::commands::{self, is_block_command, ArgumentKind};
+use crate::lexer::{lex, LexItem, Span, Spanned};
 
 const OPEN_PAREN: char = '{';
 const CLOSED_PAREN: char = '}';
 
-#[derive(Debug, Clone)]
+/// The errors that can happen while parsing or generating a Doxygen comment.
+///
+/// Every variant that points to a specific part of the comment carries the [`Span`] of it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum ParseError {
     UnexpectedEndOfInput,
     UnexpectedInput {
         found: String,
         expected: Vec<String>,
+        span: Span,
+    },
+    /// A command that requires an argument (like `@b` or `@retval`) was found without one.
+    MissingArgument {
+        command: String,
+        span: Span,
+    },
+    /// The name given to `@emoji` isn't a known emoji.
+    UnknownEmoji {
+        emoji: String,
+        span: Span,
     },
 }
 
+impl ParseError {
+    /// Returns where in the comment the error happened, if known.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ParseError::UnexpectedEndOfInput => None,
+            ParseError::UnexpectedInput { span, .. }
+            | ParseError::MissingArgument { span, .. }
+            | ParseError::UnknownEmoji { span, .. } => Some(*span),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnexpectedEndOfInput => write!(f, "unexpected end of input"),
+            ParseError::UnexpectedInput {
+                found,
+                expected,
+                span,
+            } => write!(
+                f,
+                "unexpected input `{}` at line {}, column {}, expected one of: {}",
+                found,
+                span.line,
+                span.column,
+                expected.join(", ")
+            ),
+            ParseError::MissingArgument { command, span } => write!(
+                f,
+                "`@{}` at line {}, column {} is missing its argument",
+                command, span.line, span.column
+            ),
+            ParseError::UnknownEmoji { emoji, span } => write!(
+                f,
+                "unknown emoji `{}` at line {}, column {}",
+                emoji, span.line, span.column
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum GrammarItem {
     Notation {
@@ -22,135 +82,257 @@ pub(crate) enum GrammarItem {
     Text(String),
     GroupStart,
     GroupEnd,
+    /// The start of a list item (`-`, `-#`, `@li` or `@arg`), `depth` being how many lists it's
+    /// nested in.
+    ListItem {
+        depth: usize,
+        ordered: bool,
+    },
+    /// A `.` on its own line, which ends the lists nested deeper than `depth`.
+    ListEnd {
+        depth: usize,
+    },
+    Verbatim {
+        command: String,
+        language: Option<String>,
+        content: String,
+    },
 }
 
-pub(crate) fn parse(input: String) -> Result<Vec<GrammarItem>, ParseError> {
+pub(crate) fn parse(input: String) -> Result<Vec<Spanned<GrammarItem>>, ParseError> {
     let mut lexed = lex(input);
-    lexed.push(LexItem::Space);
-    lexed.push(LexItem::Space);
-    lexed.push(LexItem::Space);
+    let end = lexed
+        .last()
+        .map(|v| Span {
+            start: v.span.end,
+            ..v.span
+        })
+        .unwrap_or_default();
+    for _ in 0..3 {
+        lexed.push(Spanned::new(LexItem::Space, end));
+    }
     parse_items(lexed)
 }
 
-fn parse_items(input: Vec<LexItem>) -> Result<Vec<GrammarItem>, ParseError> {
-    let mut grammar_items = vec![];
-    let mut param_iter_skip_count = 0;
+fn parse_items(input: Vec<Spanned<LexItem>>) -> Result<Vec<Spanned<GrammarItem>>, ParseError> {
+    let mut grammar_items: Vec<Spanned<GrammarItem>> = vec![];
+    let mut skip = 0..0;
+    // The indentation of every list the current item is nested in
+    let mut lists: Vec<usize> = vec![];
+    // Whether the current line is indented like a code block
+    let mut preformatted = false;
+
+    for (index, item) in input.windows(4).enumerate() {
+        if skip.contains(&index) {
+            continue;
+        }
 
-    for item in input.windows(4) {
-        let current = item.get(0).unwrap();
+        let current = item.first().unwrap();
         let next = item.get(1);
 
-        match current {
+        match &current.item {
             LexItem::At(_) => {
                 if let Some(next) = next {
-                    match next {
+                    let mut span = current.span.to(next.span);
+                    match &next.item {
                         LexItem::Paren(v) => match *v {
-                            OPEN_PAREN => grammar_items.push(GrammarItem::GroupStart),
-                            CLOSED_PAREN => grammar_items.push(GrammarItem::GroupEnd),
+                            OPEN_PAREN => {
+                                grammar_items.push(Spanned::new(GrammarItem::GroupStart, span))
+                            }
+                            CLOSED_PAREN => {
+                                grammar_items.push(Spanned::new(GrammarItem::GroupEnd, span))
+                            }
                             _ => {
                                 return Err(ParseError::UnexpectedInput {
                                     found: v.to_string(),
                                     expected: vec![OPEN_PAREN.into(), CLOSED_PAREN.into()],
+                                    span: next.span,
                                 })
                             }
                         },
                         LexItem::Word(v) => {
-                            let mut meta = vec![];
-                            let params;
-                            let content;
-
-                            if v.starts_with("param") {
-                                let value = v.split('[').collect::<Vec<_>>();
-                                match value.get(1) {
-                                    Some(&"in]") => meta.push("in".into()),
-                                    Some(&"out]") => meta.push("out".into()),
-                                    Some(&"in,out]") | Some(&"out,in]") => {
-                                        meta.push("in".into());
-                                        meta.push("out".into());
-                                    }
-                                    _ => match value.get(1) {
-                                        None => {}
-                                        Some(v) => {
-                                            return Err(ParseError::UnexpectedInput {
-                                                found: v.to_string(),
-                                                expected: vec!["in]".into(), "out]".into()],
-                                            })
-                                        }
-                                    },
-                                }
+                            let (content, option) = match v.split_once('[') {
+                                Some((name, option)) => (name, Some(option)),
+                                None => (v.as_str(), None),
+                            };
 
-                                params = match item.get(3) {
-                                    None => vec![],
-                                    Some(LexItem::Word(v)) => vec![v.into()],
-                                    Some(_) => vec![],
-                                };
+                            if is_block_command(content) {
+                                lists.clear();
+                            }
 
-                                content = "param"
-                            } else {
-                                content = v;
-
-                                params = match v.as_str() {
-                                    "a" | "b" | "c" | "p" | "emoji" | "e" | "em" | "def"
-                                    | "class" | "category" | "concept" | "enum" | "example"
-                                    | "extends" | "file" | "sa" | "see" | "retval"
-                                    | "exception" | "throw" | "throws" => match item.get(3) {
-                                        None => vec![],
-                                        Some(LexItem::Word(v)) => vec![v.into()],
-                                        Some(_) => vec![],
+                            if matches!(content, "li" | "arg") && option.is_none() {
+                                let indentation = line_indentation(&input, index)
+                                    .unwrap_or(current.span.column - 1);
+                                let depth = list_depth(&mut lists, indentation);
+                                grammar_items.push(Spanned::new(
+                                    GrammarItem::ListItem {
+                                        depth,
+                                        ordered: false,
                                     },
-                                    _ => vec![],
-                                };
+                                    span,
+                                ));
+                                skip = index + 1..index + 2;
+                                continue;
                             }
 
-                            if params.is_empty() {
-                                param_iter_skip_count = 1;
-                            } else {
-                                param_iter_skip_count = 2;
+                            let mut meta = vec![];
+                            match (content, option) {
+                                (_, None) => {}
+                                ("param", Some("in]")) => meta.push("in".into()),
+                                ("param", Some("out]")) => meta.push("out".into()),
+                                ("param", Some("in,out]" | "out,in]")) => {
+                                    meta.push("in".into());
+                                    meta.push("out".into());
+                                }
+                                ("param", Some(v)) => {
+                                    return Err(ParseError::UnexpectedInput {
+                                        found: v.to_string(),
+                                        expected: vec!["in]".into(), "out]".into()],
+                                        span: next.span,
+                                    })
+                                }
+                                // Like `@htmlonly[block]`
+                                (_, Some(v)) => meta.push(v.trim_end_matches(']').into()),
                             }
 
-                            grammar_items.push(GrammarItem::Notation {
-                                meta,
-                                params,
-                                tag: content.into(),
-                            });
+                            let (options, start) = command_options(&input, index + 2);
+                            meta.extend(options);
+
+                            let (params, end, rest) = command_arguments(&input, content, start);
+                            let rest = if params.is_empty() {
+                                skip = index + 1..start;
+                                None
+                            } else {
+                                span = span.to(input[end - 1].span);
+                                skip = index + 1..end;
+
+                                // Like the space after a command, keeps the words that follow
+                                // from being joined to the notation
+                                if let Some(rest) = &rest {
+                                    span.end = rest.span.start;
+                                }
+                                Some(rest.unwrap_or_else(|| {
+                                    Spanned::new(String::new(), input[end - 1].span)
+                                }))
+                            };
+
+                            grammar_items.push(Spanned::new(
+                                GrammarItem::Notation {
+                                    meta,
+                                    params,
+                                    tag: content.into(),
+                                },
+                                span,
+                            ));
+
+                            if let Some(Spanned { item, span }) = rest {
+                                grammar_items.push(Spanned::new(GrammarItem::Text(item), span));
+                            }
                         }
                         _ => {}
                     }
                 }
             }
-            LexItem::Word(v) => {
-                if param_iter_skip_count > 0 {
-                    param_iter_skip_count -= 1;
+            LexItem::Word(v)
+                if matches!(v.as_str(), "-" | "+" | "-#")
+                    && matches!(next.map(|v| &v.item), Some(LexItem::Space)) =>
+            {
+                let Some(indentation) = line_indentation(&input, index) else {
+                    push_word(&mut grammar_items, v, current.span);
                     continue;
-                }
+                };
 
-                if let Some(prev) = grammar_items.last_mut() {
-                    match prev {
-                        GrammarItem::Text(text) => *text += v,
-                        _ => grammar_items.push(GrammarItem::Text(v.into())),
-                    }
-                } else {
-                    grammar_items.push(GrammarItem::Text(v.into()));
+                let depth = list_depth(&mut lists, indentation);
+                grammar_items.push(Spanned::new(
+                    GrammarItem::ListItem {
+                        depth,
+                        ordered: v == "-#",
+                    },
+                    current.span,
+                ));
+            }
+            LexItem::Word(v) if v == "." && !lists.is_empty() && ends_line(&input, index) => {
+                let Some(indentation) = line_indentation(&input, index) else {
+                    push_word(&mut grammar_items, v, current.span);
+                    continue;
+                };
+
+                while lists.last().is_some_and(|v| *v >= indentation) {
+                    lists.pop();
                 }
+                grammar_items.push(Spanned::new(
+                    GrammarItem::ListEnd { depth: lists.len() },
+                    current.span,
+                ));
+            }
+            LexItem::Word(v) => push_word(&mut grammar_items, v, current.span),
+            // The indentation of blank lines isn't kept
+            LexItem::Indent(_) if ends_line(&input, index) => {}
+            LexItem::Indent(width) => {
+                preformatted = *width >= 4;
+                push_word(&mut grammar_items, &" ".repeat(*width), current.span);
             }
             LexItem::Space => {
+                // Spaces are only significant in preformatted lines, like ASCII tables
+                let space = if preformatted {
+                    " ".repeat(current.span.end - current.span.start)
+                } else {
+                    " ".into()
+                };
+
                 if let Some(prev) = grammar_items.last_mut() {
-                    match prev {
-                        GrammarItem::Text(text) => *text += " ",
-                        _ => grammar_items.push(GrammarItem::Text("".into())),
+                    match &mut prev.item {
+                        GrammarItem::Text(text) => {
+                            append_text(text, &mut prev.span, &space, current.span)
+                        }
+                        _ => grammar_items
+                            .push(Spanned::new(GrammarItem::Text("".into()), current.span)),
                     }
                 } else {
-                    grammar_items.push(GrammarItem::Text(" ".into()))
+                    grammar_items.push(Spanned::new(GrammarItem::Text(" ".into()), current.span))
                 }
             }
             LexItem::NewLine => {
-                if let Some(GrammarItem::Text(text)) = grammar_items.last_mut() {
-                    *text += "\n"
+                preformatted = false;
+
+                // A blank line ends every list
+                let previous = input[..index]
+                    .iter()
+                    .rev()
+                    .find(|v| !matches!(v.item, LexItem::Space | LexItem::Indent(_)));
+                if previous.is_some_and(|v| matches!(v.item, LexItem::NewLine)) {
+                    lists.clear();
                 }
+
+                if let Some(Spanned {
+                    item: GrammarItem::Text(text),
+                    span,
+                }) = grammar_items.last_mut()
+                {
+                    append_text(text, span, "\n", current.span);
+                }
+            }
+            LexItem::Verbatim {
+                command,
+                language,
+                content,
+            } => {
+                grammar_items.push(Spanned::new(
+                    GrammarItem::Verbatim {
+                        command: command.clone(),
+                        language: language.clone(),
+                        content: content.clone(),
+                    },
+                    current.span,
+                ));
             }
             LexItem::Paren(v) => {
-                if let Some(GrammarItem::Text(text)) = grammar_items.last_mut() {
-                    *text += &v.to_string()
+                if let Some(Spanned {
+                    item: GrammarItem::Text(text),
+                    span,
+                }) = grammar_items.last_mut()
+                {
+                    append_text(text, span, &v.to_string(), current.span);
                 }
             }
         }
@@ -159,13 +341,195 @@ fn parse_items(input: Vec<LexItem>) -> Result<Vec<GrammarItem>, ParseError> {
     Ok(grammar_items)
 }
 
+/// Returns the depth of a list item indented by `indentation`, after closing the lists nested
+/// deeper than it.
+fn list_depth(lists: &mut Vec<usize>, indentation: usize) -> usize {
+    while lists.last().is_some_and(|v| *v > indentation) {
+        lists.pop();
+    }
+    if lists.last() != Some(&indentation) {
+        lists.push(indentation);
+    }
+
+    lists.len() - 1
+}
+
+/// Returns the indentation of the line of the token at `index`, if it's the first one of it.
+fn line_indentation(input: &[Spanned<LexItem>], index: usize) -> Option<usize> {
+    match index.checked_sub(1).map(|v| &input[v].item) {
+        None | Some(LexItem::NewLine) => Some(0),
+        Some(LexItem::Indent(width)) => Some(*width),
+        _ => None,
+    }
+}
+
+/// Returns whether the token at `index` is the last one of its line, ignoring trailing spaces.
+fn ends_line(input: &[Spanned<LexItem>], index: usize) -> bool {
+    input[index + 1..]
+        .iter()
+        .find(|v| !matches!(v.item, LexItem::Space))
+        .map_or(true, |v| matches!(v.item, LexItem::NewLine))
+}
+
+/// Parses the `{option,option}` that can follow the name of a command (like `@image{inline}`),
+/// starting at the token after the name. Returns the options and the index of the token after
+/// them.
+fn command_options(input: &[Spanned<LexItem>], start: usize) -> (Vec<String>, usize) {
+    if !matches!(
+        input.get(start).map(|v| &v.item),
+        Some(LexItem::Paren(OPEN_PAREN))
+    ) {
+        return (vec![], start);
+    }
+
+    let mut options = String::new();
+    for (index, token) in input.iter().enumerate().skip(start + 1) {
+        match &token.item {
+            LexItem::Word(v) => options += v,
+            LexItem::Paren(CLOSED_PAREN) => {
+                let options = options.split(',').map(|v| v.trim().to_string());
+                return (options.filter(|v| !v.is_empty()).collect(), index + 1);
+            }
+            _ => break,
+        }
+    }
+
+    (vec![], start)
+}
+
+/// Parses the `<word>` and `"text"` arguments of `command` (see [`commands::COMMANDS`]), starting
+/// at the token after its name. Returns them, the index of the token after the last one and
+/// whatever follows the closing quote of the last one in the same word.
+fn command_arguments(
+    input: &[Spanned<LexItem>],
+    command: &str,
+    start: usize,
+) -> (Vec<String>, usize, Option<Spanned<String>>) {
+    let mut params = vec![];
+    let mut end = start;
+
+    for argument in commands::arguments(command) {
+        match argument.kind {
+            ArgumentKind::Word => match (input.get(end), input.get(end + 1)) {
+                (
+                    Some(Spanned {
+                        item: LexItem::Space,
+                        ..
+                    }),
+                    Some(Spanned {
+                        item: LexItem::Word(v),
+                        ..
+                    }),
+                ) => {
+                    params.push(v.clone());
+                    end += 2;
+                }
+                _ if argument.optional => {}
+                _ => break,
+            },
+            ArgumentKind::Quoted => match quoted_text(input, end) {
+                Some((text, next, rest)) => {
+                    params.push(text);
+                    end = next;
+                    if rest.is_some() {
+                        return (params, end, rest);
+                    }
+                }
+                None if argument.optional => {}
+                None => break,
+            },
+            ArgumentKind::Line | ArgumentKind::Paragraph => break,
+        }
+    }
+
+    (params, end, None)
+}
+
+/// Parses the optional `"text"` that can follow a command argument (like in `@ref name "text"`),
+/// starting at the space before it. Returns the text, the index of the token after it and
+/// whatever follows the closing quote in the same word (like punctuation), with its span.
+fn quoted_text(
+    input: &[Spanned<LexItem>],
+    start: usize,
+) -> Option<(String, usize, Option<Spanned<String>>)> {
+    if !matches!(input.get(start)?.item, LexItem::Space) {
+        return None;
+    }
+
+    let mut text = String::new();
+    for (index, token) in input.iter().enumerate().skip(start + 1) {
+        match &token.item {
+            LexItem::Word(v) => {
+                let (v, offset) = if index == start + 1 {
+                    (v.strip_prefix('"')?, 1)
+                } else {
+                    (v.as_str(), 0)
+                };
+
+                let Some((v, rest)) = v.split_once('"') else {
+                    text += v;
+                    continue;
+                };
+
+                text += v;
+                let rest = (!rest.is_empty()).then(|| {
+                    let skipped = offset + v.len() + 1;
+                    let span = Span {
+                        start: token.span.start + skipped,
+                        column: token.span.column + skipped,
+                        ..token.span
+                    };
+                    Spanned::new(rest.to_string(), span)
+                });
+                return Some((text, index + 1, rest));
+            }
+            LexItem::Space => text.push(' '),
+            LexItem::Paren(v) => text.push(*v),
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Appends `word` to the text before it, or starts a new text with it.
+fn push_word(grammar_items: &mut Vec<Spanned<GrammarItem>>, word: &str, span: Span) {
+    if let Some(Spanned {
+        item: GrammarItem::Text(text),
+        span: text_span,
+    }) = grammar_items.last_mut()
+    {
+        append_text(text, text_span, word, span);
+    } else {
+        grammar_items.push(Spanned::new(GrammarItem::Text(word.into()), span));
+    }
+}
+
+fn append_text(text: &mut String, span: &mut Span, value: &str, value_span: Span) {
+    if text.is_empty() {
+        *span = value_span;
+    } else {
+        *span = span.to(value_span);
+    }
+
+    *text += value;
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    fn parse_grammar(input: &str) -> Vec<GrammarItem> {
+        parse(input.into())
+            .unwrap()
+            .into_iter()
+            .map(|v| v.item)
+            .collect()
+    }
+
     #[test]
     pub fn simple_notation() {
-        let result = parse("@name Memory Management".into()).unwrap();
+        let result = parse_grammar("@name Memory Management");
         assert_eq!(
             result,
             vec![
@@ -181,7 +545,7 @@ mod test {
 
     #[test]
     pub fn paren_in_notation() {
-        let result = parse("@note hoge_t = {a, b, c}".into()).unwrap();
+        let result = parse_grammar("@note hoge_t = {a, b, c}");
         assert_eq!(
             result,
             vec![
@@ -198,7 +562,7 @@ mod test {
     #[test]
     pub fn param() {
         let result =
-            parse("@param[in] random This is, without a doubt, a random argument.".into()).unwrap();
+            parse_grammar("@param[in] random This is, without a doubt, a random argument.");
         assert_eq!(
             result,
             vec![
@@ -214,7 +578,7 @@ mod test {
 
     #[test]
     pub fn groups() {
-        let result = parse("@{\n* @name Memory Management\n@}".into()).unwrap();
+        let result = parse_grammar("@{\n* @name Memory Management\n@}");
         assert_eq!(
             result,
             vec![
@@ -233,7 +597,9 @@ mod test {
 
     #[test]
     pub fn trims_param_texts() {
-        let result = parse("@param[in]           var                                         Example description".into()).unwrap();
+        let result = parse_grammar(
+            "@param[in]           var                                         Example description",
+        );
         assert_eq!(
             result,
             vec![
@@ -246,4 +612,175 @@ mod test {
             ]
         )
     }
+
+    #[test]
+    pub fn spans() {
+        let result = parse("Text\n@param[in] var Desc\n@{".into()).unwrap();
+        let spans = result.iter().map(|v| v.span).collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                Span {
+                    start: 0,
+                    end: 5,
+                    line: 1,
+                    column: 1
+                },
+                Span {
+                    start: 5,
+                    end: 19,
+                    line: 2,
+                    column: 1
+                },
+                Span {
+                    start: 19,
+                    end: 25,
+                    line: 2,
+                    column: 15
+                },
+                Span {
+                    start: 25,
+                    end: 27,
+                    line: 3,
+                    column: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn error_spans() {
+        let result = parse("Text\n@param[up] var Desc".into());
+        assert_eq!(
+            result,
+            Err(ParseError::UnexpectedInput {
+                found: "up]".into(),
+                expected: vec!["in]".into(), "out]".into()],
+                span: Span {
+                    start: 6,
+                    end: 15,
+                    line: 2,
+                    column: 2
+                },
+            })
+        );
+    }
+
+    #[test]
+    pub fn verbatim() {
+        let result = parse_grammar("@brief Example\n@code{.cpp}\nstd::vector<int> a{};\n@endcode");
+        assert_eq!(
+            result,
+            vec![
+                GrammarItem::Notation {
+                    meta: vec![],
+                    params: vec![],
+                    tag: "brief".into(),
+                },
+                GrammarItem::Text("Example\n".into()),
+                GrammarItem::Verbatim {
+                    command: "code".into(),
+                    language: Some("cpp".into()),
+                    content: "std::vector<int> a{};".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn reference() {
+        let result =
+            parse_grammar("See @ref MemOp and @ref MEMOP_MAP \"the map operation\" for more.");
+        assert_eq!(
+            result,
+            vec![
+                GrammarItem::Text("See ".into()),
+                GrammarItem::Notation {
+                    meta: vec![],
+                    params: vec!["MemOp".into()],
+                    tag: "ref".into(),
+                },
+                GrammarItem::Text(" and ".into()),
+                GrammarItem::Notation {
+                    meta: vec![],
+                    params: vec!["MEMOP_MAP".into(), "the map operation".into()],
+                    tag: "ref".into(),
+                },
+                GrammarItem::Text(" for more.".into()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn lists() {
+        let result = parse_grammar("- a\n  -# b\n  .\n@li c\n\n - d");
+        assert_eq!(
+            result,
+            vec![
+                GrammarItem::ListItem {
+                    depth: 0,
+                    ordered: false,
+                },
+                GrammarItem::Text("a\n  ".into()),
+                GrammarItem::ListItem {
+                    depth: 1,
+                    ordered: true,
+                },
+                GrammarItem::Text("b\n  ".into()),
+                GrammarItem::ListEnd { depth: 1 },
+                GrammarItem::ListItem {
+                    depth: 0,
+                    ordered: false,
+                },
+                GrammarItem::Text("c\n\n ".into()),
+                GrammarItem::ListItem {
+                    depth: 0,
+                    ordered: false,
+                },
+                GrammarItem::Text("d".into()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn command_table() {
+        let result = parse_grammar(
+            "@tparam T The type\n@anchor memop\n@image{inline} html map.png \"The map\"\n@snippet example.c Mapping\n@xrefitem todo \"To do\" \"To do List\" Finish it",
+        );
+        assert_eq!(
+            result,
+            vec![
+                GrammarItem::Notation {
+                    meta: vec![],
+                    params: vec!["T".into()],
+                    tag: "tparam".into(),
+                },
+                GrammarItem::Text(" The type\n".into()),
+                GrammarItem::Notation {
+                    meta: vec![],
+                    params: vec!["memop".into()],
+                    tag: "anchor".into(),
+                },
+                GrammarItem::Text("\n".into()),
+                GrammarItem::Notation {
+                    meta: vec!["inline".into()],
+                    params: vec!["html".into(), "map.png".into(), "The map".into()],
+                    tag: "image".into(),
+                },
+                GrammarItem::Text("\n".into()),
+                GrammarItem::Notation {
+                    meta: vec![],
+                    params: vec!["example.c".into()],
+                    tag: "snippet".into(),
+                },
+                GrammarItem::Text(" Mapping\n".into()),
+                GrammarItem::Notation {
+                    meta: vec![],
+                    params: vec!["todo".into(), "To do".into(), "To do List".into()],
+                    tag: "xrefitem".into(),
+                },
+                GrammarItem::Text(" Finish it".into()),
+            ]
+        );
+    }
 }
@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::panic::RefUnwindSafe;
+
+/// Maps the C symbols referenced by a comment (with `@ref`, `@link`, `@sa`, `@see`, `@throw` or
+/// `@copydoc`) to the target of their link.
+///
+/// Bindgen renames many symbols (enum variants become `MemOp_MEMOP_MAP`, some types get a `_`
+/// suffix...), so a link to the C name is usually broken. The target returned by a resolver can be
+/// a Rust path, which rustdoc resolves like any other intra-doc link, or a URL.
+///
+/// A [`HashMap`] from the C names to the targets can be used as a resolver.
+///
+/// Resolvers are shared by the [`TransformOptions`](crate::TransformOptions) they are given to,
+/// so they must be [`Send`], [`Sync`] and [`RefUnwindSafe`] for the options to be too.
+///
+/// # Examples
+///
+/// ```
+/// use doxygen_rs::{transform_with, TransformOptions};
+/// use std::collections::HashMap;
+///
+/// let symbols = HashMap::from([("MEMOP_MAP".to_string(), "MemOp_MEMOP_MAP".to_string())]);
+/// let options = TransformOptions::new().symbol_resolver(symbols);
+///
+/// let rustdoc = transform_with("@ref MEMOP_MAP", &options);
+/// assert_eq!(rustdoc, "[`MEMOP_MAP`](MemOp_MEMOP_MAP)");
+/// ```
+pub trait SymbolResolver: Debug + Send + Sync + RefUnwindSafe {
+    /// Returns the target of the links to `symbol`, or `None` to link to the symbol itself.
+    fn resolve(&self, symbol: &str) -> Option<String>;
+}
+
+impl SymbolResolver for HashMap<String, String> {
+    fn resolve(&self, symbol: &str) -> Option<String> {
+        self.get(symbol).cloned()
+    }
+}
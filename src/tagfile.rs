@@ -0,0 +1,433 @@
+//! Links to the symbols documented in Doxygen tag files.
+//!
+//! Doxygen can describe every symbol it documents, along with the page documenting it, in a tag
+//! file (see `GENERATE_TAGFILE`). A [`TagFile`] is a [`SymbolResolver`] that links the
+//! references to those symbols to their Doxygen pages, which is useful when the documented
+//! library references symbols from other libraries that aren't part of the bindings.
+//!
+//! # Examples
+//!
+//! ```
+//! use doxygen_rs::tagfile::TagFile;
+//! use doxygen_rs::{transform_with, TransformOptions};
+//! use std::collections::HashMap;
+//!
+//! let tags = r#"<tagfile>
+//!   <compound kind="file">
+//!     <name>svc.h</name>
+//!     <filename>svc_8h.html</filename>
+//!     <member kind="function">
+//!       <name>svcControlMemory</name>
+//!       <anchorfile>svc_8h.html</anchorfile>
+//!       <anchor>a8d0d1d8d</anchor>
+//!     </member>
+//!     <member kind="function">
+//!       <name>svcExitProcess</name>
+//!       <anchorfile>svc_8h.html</anchorfile>
+//!       <anchor>a5b7a2c54</anchor>
+//!     </member>
+//!   </compound>
+//! </tagfile>"#;
+//!
+//! let symbols = HashMap::from([("svcExitProcess".to_string(), "ctru::svc::exit".to_string())]);
+//! let tag_file = TagFile::parse(tags, "https://libctru.devkitpro.org")
+//!     .unwrap()
+//!     .with_resolver(symbols);
+//! let options = TransformOptions::new().symbol_resolver(tag_file);
+//!
+//! let rustdoc = transform_with("Calls @ref svcControlMemory and @ref svcExitProcess.", &options);
+//! assert_eq!(
+//!     rustdoc,
+//!     "Calls [`svcControlMemory`](https://libctru.devkitpro.org/svc_8h.html#a8d0d1d8d) and [`svcExitProcess`](ctru::svc::exit)."
+//! );
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::SymbolResolver;
+
+/// The symbols of a Doxygen tag file and the URLs of their documentation.
+#[derive(Debug, Default)]
+pub struct TagFile {
+    urls: HashMap<String, String>,
+    resolver: Option<Box<dyn SymbolResolver>>,
+}
+
+/// An error while reading a tag file.
+#[derive(Debug)]
+pub enum TagFileError {
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    UnexpectedEndOfInput,
+    /// An element was closed by the end tag of another one.
+    MismatchedTag {
+        expected: String,
+        found: String,
+        line: usize,
+    },
+}
+
+impl std::fmt::Display for TagFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TagFileError::Io(err) => write!(f, "failed to read the tag file: {err}"),
+            TagFileError::UnexpectedEndOfInput => write!(f, "unexpected end of input"),
+            TagFileError::MismatchedTag {
+                expected,
+                found,
+                line,
+            } => write!(
+                f,
+                "unexpected `</{found}>` at line {line}, expected `</{expected}>`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TagFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TagFileError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for TagFileError {
+    fn from(err: std::io::Error) -> Self {
+        TagFileError::Io(err)
+    }
+}
+
+impl TagFile {
+    /// Reads the tag file at `path`. The URLs of the pages are relative to `base_url`, where the
+    /// HTML documentation of the library is published.
+    ///
+    /// # Errors
+    ///
+    /// This function will error if the file can't be read or isn't a valid tag file, see
+    /// [`TagFileError`].
+    pub fn open(path: impl AsRef<Path>, base_url: &str) -> Result<Self, TagFileError> {
+        Self::parse(&std::fs::read_to_string(path)?, base_url)
+    }
+
+    /// Parses the content of a tag file, see [`TagFile::open`].
+    ///
+    /// # Errors
+    ///
+    /// This function will error if the content isn't a valid tag file, see [`TagFileError`].
+    pub fn parse(content: &str, base_url: &str) -> Result<Self, TagFileError> {
+        let base_url = base_url.trim_end_matches('/');
+        let mut urls = HashMap::new();
+
+        for compound in parse_xml(content)?
+            .iter()
+            .flat_map(|v| v.children("compound"))
+        {
+            let Some(name) = compound.child_text("name") else {
+                continue;
+            };
+
+            if let Some(file) = compound.child_text("filename") {
+                urls.entry(name.to_string())
+                    .or_insert_with(|| format!("{base_url}/{}", html_file(file)));
+            }
+
+            // Members of classes and namespaces are referenced with their qualified name
+            let scope = match compound.attribute("kind") {
+                Some("class" | "struct" | "union" | "namespace" | "interface") => {
+                    Some(name.trim_end_matches("::"))
+                }
+                _ => None,
+            };
+
+            for member in compound.children("member") {
+                let (Some(name), Some(file)) =
+                    (member.child_text("name"), member.child_text("anchorfile"))
+                else {
+                    continue;
+                };
+
+                let url = match member.child_text("anchor") {
+                    Some(anchor) if !anchor.is_empty() => {
+                        format!("{base_url}/{}#{anchor}", html_file(file))
+                    }
+                    _ => format!("{base_url}/{}", html_file(file)),
+                };
+
+                let name = match scope {
+                    Some(scope) => format!("{scope}::{name}"),
+                    None => name.to_string(),
+                };
+                // A symbol is listed by every file and group it's part of, all with the same page
+                urls.entry(name).or_insert(url);
+            }
+        }
+
+        Ok(Self {
+            urls,
+            resolver: None,
+        })
+    }
+
+    /// Sets the resolver consulted before the tag file, so the symbols that are part of the
+    /// bindings get intra-doc links instead of links to their Doxygen pages.
+    pub fn with_resolver(mut self, resolver: impl SymbolResolver + 'static) -> Self {
+        self.resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// Returns the URL of the page documenting `symbol`, if the tag file has it.
+    pub fn url(&self, symbol: &str) -> Option<&str> {
+        self.urls.get(symbol).map(String::as_str)
+    }
+}
+
+impl SymbolResolver for TagFile {
+    fn resolve(&self, symbol: &str) -> Option<String> {
+        self.resolver
+            .as_ref()
+            .and_then(|v| v.resolve(symbol))
+            .or_else(|| self.url(symbol).map(String::from))
+    }
+}
+
+/// Older versions of Doxygen leave the extension out of the compound file names.
+fn html_file(file: &str) -> String {
+    if file.ends_with(".html") {
+        file.into()
+    } else {
+        format!("{file}.html")
+    }
+}
+
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |v| v.name == name)
+    }
+
+    fn child_text(&self, name: &str) -> Option<&str> {
+        self.children
+            .iter()
+            .find(|v| v.name == name)
+            .map(|v| v.text.trim())
+    }
+}
+
+/// Parses the subset of XML used by tag files into the top-level elements.
+fn parse_xml(input: &str) -> Result<Vec<Element>, TagFileError> {
+    let line = |position: usize| input[..position].matches('\n').count() + 1;
+    let mut stack = vec![Element::default()];
+    let mut position = 0;
+
+    while let Some(start) = input[position..].find('<').map(|v| position + v) {
+        let text = unescape(&input[position..start]);
+        stack.last_mut().unwrap().text += &text;
+
+        let tag = &input[start..];
+        // Declarations, comments and doctypes
+        let skipped = [("<?", "?>"), ("<!--", "-->"), ("<!", ">")]
+            .iter()
+            .find(|(open, _)| tag.starts_with(open));
+        if let Some((_, close)) = skipped {
+            let end = tag.find(close).ok_or(TagFileError::UnexpectedEndOfInput)?;
+            position = start + end + close.len();
+            continue;
+        }
+
+        let end = tag.find('>').ok_or(TagFileError::UnexpectedEndOfInput)?;
+        let content = &tag[1..end];
+        position = start + end + 1;
+
+        if let Some(name) = content.strip_prefix('/') {
+            let name = name.trim();
+            let element = stack.pop().filter(|_| !stack.is_empty()).ok_or_else(|| {
+                TagFileError::MismatchedTag {
+                    expected: String::new(),
+                    found: name.into(),
+                    line: line(start),
+                }
+            })?;
+            if element.name != name {
+                return Err(TagFileError::MismatchedTag {
+                    expected: element.name,
+                    found: name.into(),
+                    line: line(start),
+                });
+            }
+            stack.last_mut().unwrap().children.push(element);
+            continue;
+        }
+
+        let self_closing = content.ends_with('/');
+        let content = content.trim_end_matches('/');
+        let (name, attributes) = content
+            .split_once(char::is_whitespace)
+            .unwrap_or((content, ""));
+        let element = Element {
+            name: name.into(),
+            attributes: parse_attributes(attributes),
+            ..Element::default()
+        };
+
+        if self_closing {
+            stack.last_mut().unwrap().children.push(element);
+        } else {
+            stack.push(element);
+        }
+    }
+
+    match stack.pop() {
+        Some(root) if stack.is_empty() => Ok(root.children),
+        _ => Err(TagFileError::UnexpectedEndOfInput),
+    }
+}
+
+fn parse_attributes(mut input: &str) -> Vec<(String, String)> {
+    let mut attributes = vec![];
+
+    while let Some((key, rest)) = input.split_once('=') {
+        let rest = rest.trim_start();
+        let Some(quote) = rest.chars().next().filter(|v| *v == '"' || *v == '\'') else {
+            break;
+        };
+        let Some((value, rest)) = rest[1..].split_once(quote) else {
+            break;
+        };
+
+        attributes.push((key.trim().to_string(), unescape(value)));
+        input = rest;
+    }
+
+    attributes
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TAGS: &str = r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?>
+<tagfile doxygen_version="1.9.8">
+  <!-- A comment -->
+  <compound kind="struct">
+    <name>MemInfo</name>
+    <filename>structMemInfo</filename>
+    <member kind="variable">
+      <type>u32</type>
+      <name>base_addr</name>
+      <anchorfile>structMemInfo.html</anchorfile>
+      <anchor>a1</anchor>
+      <arglist></arglist>
+    </member>
+  </compound>
+  <compound kind="file">
+    <name>svc.h</name>
+    <filename>svc_8h.html</filename>
+    <class kind="struct">MemInfo</class>
+    <member kind="enumvalue">
+      <name>MEMOP_MAP</name>
+      <anchorfile>svc_8h.html</anchorfile>
+      <anchor>a2</anchor>
+      <arglist></arglist>
+    </member>
+    <member kind="function">
+      <type>Result</type>
+      <name>svcControlMemory</name>
+      <anchorfile>svc_8h.html</anchorfile>
+      <anchor>a3</anchor>
+      <arglist>(u32 *addr_out, MemOp op)</arglist>
+    </member>
+  </compound>
+  <compound kind="group">
+    <name>svc</name>
+    <filename>group__svc.html</filename>
+    <member kind="function">
+      <name>svcControlMemory</name>
+      <anchorfile>group__svc.html</anchorfile>
+      <anchor>a4</anchor>
+    </member>
+  </compound>
+</tagfile>
+"#;
+
+    #[test]
+    fn urls() {
+        let tag_file = TagFile::parse(TAGS, "https://example.com/docs/").unwrap();
+
+        assert_eq!(
+            tag_file.url("MemInfo"),
+            Some("https://example.com/docs/structMemInfo.html")
+        );
+        assert_eq!(
+            tag_file.url("MemInfo::base_addr"),
+            Some("https://example.com/docs/structMemInfo.html#a1")
+        );
+        assert_eq!(
+            tag_file.url("MEMOP_MAP"),
+            Some("https://example.com/docs/svc_8h.html#a2")
+        );
+        assert_eq!(
+            tag_file.url("svcControlMemory"),
+            Some("https://example.com/docs/svc_8h.html#a3")
+        );
+        assert_eq!(tag_file.url("base_addr"), None);
+    }
+
+    #[test]
+    fn resolver() {
+        let symbols = HashMap::from([("MEMOP_MAP".to_string(), "MemOp_MEMOP_MAP".to_string())]);
+        let tag_file = TagFile::parse(TAGS, "https://example.com")
+            .unwrap()
+            .with_resolver(symbols);
+
+        assert_eq!(
+            tag_file.resolve("MEMOP_MAP"),
+            Some("MemOp_MEMOP_MAP".into())
+        );
+        assert_eq!(
+            tag_file.resolve("MemInfo"),
+            Some("https://example.com/structMemInfo.html".into())
+        );
+        assert_eq!(tag_file.resolve("Unknown"), None);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            TagFile::parse("<tagfile>\n<compound></member>", ""),
+            Err(TagFileError::MismatchedTag { line: 2, .. })
+        ));
+        assert!(matches!(
+            TagFile::parse("<tagfile><compound>", ""),
+            Err(TagFileError::UnexpectedEndOfInput)
+        ));
+        assert!(matches!(
+            TagFile::open("does/not/exist.tag", ""),
+            Err(TagFileError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,288 @@
+use crate::ast::{self, Block};
+use crate::doc::plain_text;
+use crate::generator::generate;
+use crate::normalize::normalize;
+use crate::{GroupStyle, ParseError, TransformOptions};
+
+/// Transforms the comments of consecutive elements, keeping track of the member groups
+/// (`@name` and `@{`/`@}`) opened and closed across them.
+///
+/// Bindgen processes the comment of every element on its own, so a group opened in the comment
+/// of one function and closed several declarations later can't be handled by
+/// [`transform`](crate::transform). A `Transformer` removes the group commands from the output
+/// and marks every member of the group according to [`TransformOptions::group_style`].
+///
+/// # Examples
+///
+/// ```
+/// use doxygen_rs::{GroupStyle, TransformOptions, Transformer};
+///
+/// let options = TransformOptions::new().group_style(GroupStyle::Annotate);
+/// let mut transformer = Transformer::with_options(options);
+///
+/// let first = transformer.transform("@name Memory management\n@{\n@brief Maps memory").unwrap();
+/// assert_eq!(first, "Maps memory\n\n> **Group:** Memory management");
+///
+/// let second = transformer.transform("@brief Frees memory").unwrap();
+/// assert_eq!(second, "Frees memory\n\n> **Group:** Memory management");
+///
+/// let third = transformer.transform("@}\n@brief Unrelated").unwrap();
+/// assert_eq!(third, "Unrelated");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Transformer {
+    options: TransformOptions,
+    groups: Vec<Option<String>>,
+    pending_name: Option<String>,
+}
+
+impl Transformer {
+    /// Creates a transformer with the default [`TransformOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a transformer with the given [`TransformOptions`].
+    pub fn with_options(options: TransformOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the name of the innermost member group currently open, if it has one.
+    pub fn current_group(&self) -> Option<&str> {
+        self.groups.last()?.as_deref()
+    }
+
+    /// Transforms the comment of the next element.
+    ///
+    /// # Errors
+    ///
+    /// See [`generator::rustdoc_with`](crate::generator::rustdoc_with). The open groups are left
+    /// untouched when an error is returned.
+    pub fn transform(&mut self, comment: &str) -> Result<String, ParseError> {
+        let comment = &normalize(comment);
+        let parsed = ast::parse(comment)?;
+        let mut groups = self.groups.clone();
+        let mut pending_name = self.pending_name.clone();
+        let mut member_of = None;
+        let mut removed = vec![];
+
+        for block in &parsed.blocks {
+            match block {
+                Block::Command {
+                    command, content, ..
+                } if command.name == "name" => {
+                    let name = plain_text(content);
+                    let name = name.lines().next().unwrap_or_default();
+                    pending_name = Some(name.to_string()).filter(|v| !v.is_empty());
+
+                    let end = comment[command.span.end..]
+                        .find('\n')
+                        .map(|v| command.span.end + v)
+                        .unwrap_or(comment.len());
+                    removed.push(command.span.start..end);
+                }
+                Block::GroupStart { span } => {
+                    groups.push(pending_name.take());
+                    removed.push(span.start..span.end);
+                }
+                Block::GroupEnd { span } => {
+                    groups.pop();
+                    removed.push(span.start..span.end);
+                }
+                _ => {
+                    if member_of.is_none() {
+                        member_of = Some(groups.last().cloned().flatten());
+                    }
+                }
+            }
+        }
+
+        // The `@name` line can contain the group commands too, so the ranges can overlap
+        removed.sort_by_key(|v| v.start);
+
+        let mut stripped = String::new();
+        let mut last = 0;
+        for range in removed {
+            if range.end <= last {
+                continue;
+            }
+            stripped += &comment[last.min(range.start)..range.start];
+            last = range.end;
+
+            // Don't leave an empty line behind if the command was alone on it
+            if stripped.ends_with('\n') || stripped.is_empty() {
+                let rest = &comment[last..];
+                let whitespace = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+                if rest[whitespace..].starts_with('\n') {
+                    last += whitespace + 1;
+                }
+            }
+        }
+        stripped += &comment[last..];
+
+        let result = generate(stripped, &self.options)?;
+        self.groups = groups;
+        self.pending_name = pending_name;
+
+        let Some(Some(name)) = member_of else {
+            return Ok(result);
+        };
+
+        Ok(match self.options.group_style {
+            GroupStyle::Ignore => result,
+            GroupStyle::Prefix => {
+                if result.is_empty() {
+                    format!("**{name}:**")
+                } else if result.starts_with(['#', '>', '*', '-', '`', '|']) {
+                    format!("**{name}:**\n\n{result}")
+                } else {
+                    format!("**{name}:** {result}")
+                }
+            }
+            GroupStyle::Annotate => {
+                let annotation = self.options.note(&format!("**Group:** {name}"));
+                if result.trim_end().is_empty() {
+                    annotation
+                } else {
+                    format!("{}\n\n{annotation}", result.trim_end())
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn groups_across_items() {
+        let mut transformer =
+            Transformer::with_options(TransformOptions::new().group_style(GroupStyle::Prefix));
+
+        assert_eq!(
+            transformer
+                .transform(
+                    "@name Memory management\n@{\n@brief Maps memory\n@param addr The address"
+                )
+                .unwrap(),
+            "**Memory management:** Maps memory\n# Arguments\n\n* `addr` - The address"
+        );
+        assert_eq!(transformer.current_group(), Some("Memory management"));
+
+        assert_eq!(
+            transformer.transform("@param addr The address").unwrap(),
+            "**Memory management:**\n\n# Arguments\n\n* `addr` - The address"
+        );
+
+        assert_eq!(transformer.transform("@}").unwrap(), "");
+        assert_eq!(transformer.current_group(), None);
+
+        assert_eq!(transformer.transform("@brief Outside").unwrap(), "Outside");
+    }
+
+    #[test]
+    fn unmarked_by_default() {
+        let mut transformer = Transformer::new();
+
+        assert_eq!(
+            transformer.transform("@name Group\n@{\nFirst").unwrap(),
+            "First"
+        );
+        assert_eq!(transformer.transform("Second").unwrap(), "Second");
+        assert_eq!(transformer.current_group(), Some("Group"));
+    }
+
+    #[test]
+    fn nested_groups() {
+        let mut transformer =
+            Transformer::with_options(TransformOptions::new().group_style(GroupStyle::Annotate));
+
+        transformer.transform("@name Outer\n@{").unwrap();
+        assert_eq!(
+            transformer.transform("@name Inner\n@{\nInside").unwrap(),
+            "Inside\n\n> **Group:** Inner"
+        );
+        assert_eq!(
+            transformer.transform("@}\nOutside inner").unwrap(),
+            "Outside inner\n\n> **Group:** Outer"
+        );
+    }
+
+    #[test]
+    fn unnamed_groups() {
+        let mut transformer =
+            Transformer::with_options(TransformOptions::new().group_style(GroupStyle::Annotate));
+
+        assert_eq!(transformer.transform("@{\nNo name").unwrap(), "No name");
+        assert_eq!(transformer.current_group(), None);
+    }
+
+    #[test]
+    fn group_commands_on_name_line() {
+        let mut transformer =
+            Transformer::with_options(TransformOptions::new().group_style(GroupStyle::Annotate));
+
+        assert_eq!(transformer.transform("@name Memory @{").unwrap(), "");
+        assert_eq!(transformer.current_group(), Some("Memory"));
+        assert_eq!(
+            transformer.transform("Maps memory").unwrap(),
+            "Maps memory\n\n> **Group:** Memory"
+        );
+
+        assert_eq!(transformer.transform("@name @}").unwrap(), "");
+        assert_eq!(transformer.current_group(), None);
+    }
+
+    #[test]
+    fn errors_keep_state() {
+        let mut transformer = Transformer::new();
+
+        assert!(transformer.transform("@name Group\n@{\n@b").is_err());
+        assert_eq!(transformer.current_group(), None);
+    }
+
+    #[test]
+    fn can_transform_example() {
+        let items = include_str!("../tests/assets/example-bindgen-groups.rs")
+            .split("extern \"C\"")
+            .skip(1)
+            .map(|item| {
+                item.lines()
+                    .filter_map(|v| v.trim().strip_prefix("#[doc = \"")?.strip_suffix("\"]"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect::<Vec<_>>();
+
+        let mut transformer =
+            Transformer::with_options(TransformOptions::new().group_style(GroupStyle::Annotate));
+
+        assert_eq!(
+            transformer.transform(&items[0]).unwrap(),
+            "Controls memory mapping\n# Arguments\n\n* `addr_out` (direction out) - The virtual address resulting from the operation. Usually the same as addr0.\n* `addr0` - The virtual address to be used for the operation.\n* `size` - The requested size for [`MEMOP_ALLOC`] and [`MEMOP_ALLOC_LINEAR`].\n\n> **Group:** Memory management"
+        );
+        assert_eq!(transformer.current_group(), Some("Memory management"));
+
+        assert_eq!(
+            transformer.transform(&items[1]).unwrap(),
+            "Unmaps a block of process memory.\n# Arguments\n\n* `process` - Handle of the process to unmap the memory of.\n\n> **Group:** Memory management"
+        );
+        assert_eq!(transformer.current_group(), Some("Memory management"));
+
+        assert_eq!(
+            transformer.transform(&items[2]).unwrap(),
+            "Gets the handle of a process.\n# Arguments\n\n* `process` (direction out) - Pointer to output the process handle to.\n\n> **Group:** Process management"
+        );
+        assert_eq!(transformer.current_group(), Some("Process management"));
+
+        assert_eq!(
+            transformer.transform(&items[3]).unwrap(),
+            "Gets the current system tick."
+        );
+        assert_eq!(transformer.current_group(), None);
+    }
+}
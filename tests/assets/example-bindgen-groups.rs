@@ -0,0 +1,39 @@
+extern "C" {
+    #[must_use]
+    #[doc = "@name Memory management"]
+    #[doc = "@{"]
+    #[doc = "**"]
+    #[doc = "* @brief Controls memory mapping"]
+    #[doc = "* @param[out] addr_out The virtual address resulting from the operation. Usually the same as addr0."]
+    #[doc = "* @param addr0    The virtual address to be used for the operation."]
+    #[doc = "* @param size     The requested size for @ref MEMOP_ALLOC and @ref MEMOP_ALLOC_LINEAR."]
+    #[doc = "*/"]
+    pub fn svcControlMemory(addr_out: *mut u32_, addr0: u32_, size: u32_) -> Result;
+}
+extern "C" {
+    #[must_use]
+    #[doc = "**"]
+    #[doc = "* @brief Unmaps a block of process memory."]
+    #[doc = "* @param process Handle of the process to unmap the memory of."]
+    #[doc = "*/"]
+    pub fn svcUnmapProcessMemoryEx(process: Handle) -> Result;
+}
+extern "C" {
+    #[must_use]
+    #[doc = "@}"]
+    #[doc = "@name Process management"]
+    #[doc = "@{"]
+    #[doc = "**"]
+    #[doc = "* @brief Gets the handle of a process."]
+    #[doc = "* @param[out] process Pointer to output the process handle to."]
+    #[doc = "*/"]
+    pub fn svcOpenProcess(process: *mut Handle) -> Result;
+}
+extern "C" {
+    #[must_use]
+    #[doc = "@}"]
+    #[doc = "**"]
+    #[doc = "* @brief Gets the current system tick."]
+    #[doc = "*/"]
+    pub fn svcGetSystemTick() -> u64_;
+}